@@ -0,0 +1,145 @@
+use crate::matrix::Matrix;
+use crate::pos::{Neighborhood, Pos};
+
+/// Applies a local rule to every cell of a [`Matrix`] at once, generation
+/// after generation.
+///
+/// The rule gets the current cell and its in-bounds neighbours, and returns
+/// the cell for the next generation.
+pub struct Automaton<'a, T, F> {
+    current: Matrix<T>,
+    next: Matrix<T>,
+    neighborhood: Neighborhood<'a>,
+    rule: F,
+    generation: usize,
+}
+
+/// What happened during [`Automaton::run`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunSummary {
+    /// How many cells changed, for each generation computed.
+    pub changes: Vec<usize>,
+    /// Whether the run stopped because a generation changed nothing.
+    pub fixed_point: bool,
+}
+
+impl RunSummary {
+    pub fn generations(&self) -> usize {
+        self.changes.len()
+    }
+}
+
+impl<'a, T, F> Automaton<'a, T, F>
+where
+    T: Clone + PartialEq,
+    F: FnMut(&T, &[&T]) -> T,
+{
+    pub fn new(matrix: Matrix<T>, neighborhood: Neighborhood<'a>, rule: F) -> Self {
+        Automaton {
+            next: matrix.clone(),
+            current: matrix,
+            neighborhood,
+            rule,
+            generation: 0,
+        }
+    }
+
+    pub fn matrix(&self) -> &Matrix<T> {
+        &self.current
+    }
+
+    pub fn into_matrix(self) -> Matrix<T> {
+        self.current
+    }
+
+    /// How many generations were computed so far.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Computes the next generation and returns how many cells changed.
+    pub fn step(&mut self) -> usize {
+        let mut changed = 0;
+        let mut neighbors = Vec::with_capacity(8);
+        for (i, cell) in self.current.vec.iter().enumerate() {
+            let pos = Pos::from((i % self.current.width(), i / self.current.width()));
+            neighbors.clear();
+            neighbors.extend(
+                self.current
+                    .neighbors_with(pos, self.neighborhood)
+                    .map(|(_, t)| t),
+            );
+            let new = (self.rule)(cell, &neighbors);
+            if new != *cell {
+                changed += 1;
+            }
+            self.next.vec[i] = new;
+        }
+        std::mem::swap(&mut self.current, &mut self.next);
+        self.generation += 1;
+        changed
+    }
+
+    /// Steps until a generation changes nothing, or `max_steps` generations
+    /// were computed.
+    pub fn run(&mut self, max_steps: usize) -> RunSummary {
+        let mut changes = Vec::new();
+        for _ in 0..max_steps {
+            let changed = self.step();
+            changes.push(changed);
+            if changed == 0 {
+                return RunSummary {
+                    changes,
+                    fixed_point: true,
+                };
+            }
+        }
+        RunSummary {
+            changes,
+            fixed_point: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn life(alive: &bool, neighbors: &[&bool]) -> bool {
+        let n = neighbors.iter().filter(|b| ***b).count();
+        matches!((alive, n), (true, 2) | (_, 3))
+    }
+
+    fn grid(s: &str) -> Matrix<bool> {
+        Matrix::parse_grid(s, |c| Ok::<_, ()>(c == '#')).unwrap()
+    }
+
+    #[test]
+    fn test_blinker() {
+        let start = grid(".....\n..#..\n..#..\n..#..\n.....\n");
+        let mut automaton = Automaton::new(start.clone(), Neighborhood::King, life);
+        assert_eq!(automaton.step(), 4);
+        assert_eq!(
+            automaton.matrix(),
+            &grid(".....\n.....\n.###.\n.....\n.....\n")
+        );
+        let summary = automaton.run(5);
+        assert_eq!(summary.changes, vec![4; 5]);
+        assert!(!summary.fixed_point);
+        assert_eq!(automaton.generation(), 6);
+        assert_eq!(automaton.into_matrix(), start);
+    }
+
+    #[test]
+    fn test_fixed_point() {
+        // Fire spreading to the 4 neighbours of burning cells
+        let start = Matrix::new([0, 0, 0, 1, 0, 0], 6, 1);
+        let mut automaton = Automaton::new(start, Neighborhood::Rook, |&c, n: &[&u8]| {
+            c.max(*n.iter().copied().max().unwrap())
+        });
+        let summary = automaton.run(100);
+        assert_eq!(summary.changes, vec![2, 2, 1, 0]);
+        assert!(summary.fixed_point);
+        assert_eq!(automaton.matrix().vec, vec![1; 6]);
+    }
+}
@@ -1,30 +1,11 @@
-#![feature(iter_map_windows)]
-
 use advent2023::matrix::Matrix;
+use advent2023::pos::{Direction, Pos};
+use advent2023::render::Overlay;
+use anyhow::Result;
 use core::fmt;
 use std::time::Instant;
 use std::{fs, vec};
 
-#[derive(Debug)]
-enum Direction {
-    West,
-    South,
-    East,
-    North,
-}
-
-impl Direction {
-    fn from_delta((dx, dy): (i64, i64)) -> Option<Self> {
-        match (dx, dy) {
-            (1.., 0) => Some(Direction::East),
-            (..=-1, 0) => Some(Direction::West),
-            (0, 1..) => Some(Direction::North),
-            (0, ..=-1) => Some(Direction::South),
-            _ => None,
-        }
-    }
-}
-
 #[derive(PartialEq, Eq, Clone)]
 enum Cell {
     Ground,
@@ -53,68 +34,56 @@ impl fmt::Debug for Cell {
     }
 }
 
-fn parse(input: &str) -> Matrix<Cell> {
-    let width = input.lines().next().unwrap().chars().count();
-    let height = input.lines().count();
-    let items = input
-        .chars()
-        .filter(|c| c.is_ascii_graphic())
-        .map(|c| match c {
-            '.' => Cell::Ground,
-            '|' => Cell::Vert,
-            '-' => Cell::Horz,
-            'L' => Cell::NToE,
-            'J' => Cell::NToW,
-            '7' => Cell::SToW,
-            'F' => Cell::SToE,
-            'S' => Cell::Starting,
-            x => panic!("Unexpected char in input: {x}"),
-        });
-    Matrix::new(items, width, height)
+fn parse(input: &str) -> Result<Matrix<Cell>> {
+    let matrix = Matrix::parse_grid(input, |c| match c {
+        '.' => Ok(Cell::Ground),
+        '|' => Ok(Cell::Vert),
+        '-' => Ok(Cell::Horz),
+        'L' => Ok(Cell::NToE),
+        'J' => Ok(Cell::NToW),
+        '7' => Ok(Cell::SToW),
+        'F' => Ok(Cell::SToE),
+        'S' => Ok(Cell::Starting),
+        _ => Err("unexpected char in input"),
+    })?;
+    Ok(matrix)
 }
 
-fn find_loop(matrix: &Matrix<Cell>) -> Option<Vec<(usize, usize)>> {
+fn find_loop(matrix: &Matrix<Cell>) -> Option<Vec<Pos>> {
     // Find the starting pos
-    let spos = matrix.index_of(|c| *c == Cell::Starting).unwrap();
+    let spos = Pos::from(matrix.index_of(|c| *c == Cell::Starting).unwrap());
 
     // Identify which way is the loop
-    matrix
-        .rook_neighbor_indices(spos.0, spos.1)
-        .find_map(|pos| {
-            let mut last_visited = (spos.0 as i64, spos.1 as i64);
-            let mut current = (pos.0 as i64, pos.1 as i64);
-            let mut visited = vec![spos];
-
-            loop {
-                use Cell as C;
-                use Direction as D;
-                // Follow the path
-                visited.push((current.0 as usize, current.1 as usize));
-                let last_step =
-                    Direction::from_delta((current.0 - last_visited.0, last_visited.1 - current.1))
-                        .unwrap();
-                last_visited = current;
-                let cur_cell = &matrix[(current.0 as usize, current.1 as usize)];
-                let delta = match (last_step, cur_cell) {
-                    (_, C::Ground) => return None, // invalid path
-                    (D::North, C::Vert) => (0, -1),
-                    (D::South, C::Vert) => (0, 1),
-                    (D::East, C::Horz) => (1, 0),
-                    (D::West, C::Horz) => (-1, 0),
-                    (D::South, C::NToE) => (1, 0),
-                    (D::West, C::NToE) => (0, -1),
-                    (D::South, C::NToW) => (-1, 0),
-                    (D::East, C::NToW) => (0, -1),
-                    (D::North, C::SToW) => (-1, 0),
-                    (D::East, C::SToW) => (0, 1),
-                    (D::North, C::SToE) => (1, 0),
-                    (D::West, C::SToE) => (0, 1),
-                    (_, C::Starting) => return Some(visited),
-                    _ => return None, // invalid path
-                };
-                current = (current.0 + delta.0, current.1 + delta.1);
-            }
-        })
+    Direction::CARDINAL.into_iter().find_map(|first_step| {
+        let mut heading = first_step;
+        let mut current = spos.step(heading);
+        let mut visited = vec![spos];
+
+        loop {
+            use Cell as C;
+            use Direction as D;
+            // Follow the path
+            visited.push(current);
+            heading = match (heading, matrix.get_pos(current)?) {
+                (_, C::Ground) => return None, // invalid path
+                (D::North, C::Vert) => D::North,
+                (D::South, C::Vert) => D::South,
+                (D::East, C::Horz) => D::East,
+                (D::West, C::Horz) => D::West,
+                (D::South, C::NToE) => D::East,
+                (D::West, C::NToE) => D::North,
+                (D::South, C::NToW) => D::West,
+                (D::East, C::NToW) => D::North,
+                (D::North, C::SToW) => D::West,
+                (D::East, C::SToW) => D::South,
+                (D::North, C::SToE) => D::East,
+                (D::West, C::SToE) => D::South,
+                (_, C::Starting) => return Some(visited),
+                _ => return None, // invalid path
+            };
+            current = current.step(heading);
+        }
+    })
 }
 
 fn p1(matrix: &Matrix<Cell>) -> u64 {
@@ -127,7 +96,7 @@ fn p2(matrix: &Matrix<Cell>) -> u64 {
     let clean_items = (0..matrix.len())
         .map(|i| (i % matrix.width(), i / matrix.width()))
         .map(|pos| {
-            if pipe_loop.contains(&pos) {
+            if pipe_loop.contains(&Pos::from(pos)) {
                 matrix[pos].clone()
             } else {
                 Cell::Ground
@@ -137,41 +106,21 @@ fn p2(matrix: &Matrix<Cell>) -> u64 {
     let Some(spos) = clean_matrix.index_of(|cell| *cell == Cell::Starting) else {
         return 0;
     };
+    let spos = Pos::from(spos);
 
     println!("{spos:?}");
 
-    let offsets = [
-        (-1, 0), // West
-        (1, 0),  // East
-        (0, -1), // North
-        (0, 1),  // South
-    ];
+    let connects_to_start = |direction: Direction, pipes: [Cell; 3]| {
+        clean_matrix
+            .get_pos(spos.step(direction))
+            .is_some_and(|cell| pipes.contains(cell))
+    };
 
     let connects_well_to_start = [
-        clean_matrix
-            .get(
-                spos.0 as isize + offsets[0].0,
-                spos.1 as isize + offsets[0].1,
-            )
-            .is_some_and(|cell| matches!(cell, Cell::NToE | Cell::SToE | Cell::Horz)),
-        clean_matrix
-            .get(
-                spos.0 as isize + offsets[1].0,
-                spos.1 as isize + offsets[1].1,
-            )
-            .is_some_and(|cell| matches!(cell, Cell::NToW | Cell::SToW | Cell::Horz)),
-        clean_matrix
-            .get(
-                spos.0 as isize + offsets[2].0,
-                spos.1 as isize + offsets[2].1,
-            )
-            .is_some_and(|cell| matches!(cell, Cell::SToW | Cell::SToE | Cell::Vert)),
-        clean_matrix
-            .get(
-                spos.0 as isize + offsets[3].0,
-                spos.1 as isize + offsets[3].1,
-            )
-            .is_some_and(|cell| matches!(cell, Cell::NToW | Cell::NToE | Cell::Vert)),
+        connects_to_start(Direction::West, [Cell::NToE, Cell::SToE, Cell::Horz]),
+        connects_to_start(Direction::East, [Cell::NToW, Cell::SToW, Cell::Horz]),
+        connects_to_start(Direction::North, [Cell::SToW, Cell::SToE, Cell::Vert]),
+        connects_to_start(Direction::South, [Cell::NToW, Cell::NToE, Cell::Vert]),
     ];
 
     let real_spos = match connects_well_to_start {
@@ -184,44 +133,49 @@ fn p2(matrix: &Matrix<Cell>) -> u64 {
         x => panic!("Unexpected connections to start {x:?}"),
     };
 
-    println!("{clean_matrix:?}");
+    clean_matrix
+        .render(|cell| format!("{cell:?}"))
+        .overlay(Overlay::cursor(spos))
+        .print();
 
     clean_matrix[spos] = real_spos;
 
-    let mut inside = false;
-    let mut entered_border_with = None;
     let mut count = 0;
 
-    for cell in clean_matrix.vec.iter() {
-        if inside && *cell == Cell::Ground && entered_border_with.is_none() {
-            count += 1;
-        }
-        if matches!(cell, Cell::Vert) {
-            inside = !inside;
-        }
-        if matches!(cell, Cell::NToE | Cell::NToW | Cell::SToW | Cell::SToE) {
-            if let Some(entry_cell) = entered_border_with {
-                if matches!(
-                    (entry_cell, cell),
-                    (Cell::NToE, Cell::SToW) | (Cell::SToE, Cell::NToW)
-                ) {
-                    inside = !inside;
+    for row in clean_matrix.rows() {
+        let mut inside = false;
+        let mut entered_border_with = None;
+        for cell in row {
+            if inside && *cell == Cell::Ground && entered_border_with.is_none() {
+                count += 1;
+            }
+            if matches!(cell, Cell::Vert) {
+                inside = !inside;
+            }
+            if matches!(cell, Cell::NToE | Cell::NToW | Cell::SToW | Cell::SToE) {
+                if let Some(entry_cell) = entered_border_with {
+                    if matches!(
+                        (entry_cell, cell),
+                        (Cell::NToE, Cell::SToW) | (Cell::SToE, Cell::NToW)
+                    ) {
+                        inside = !inside;
+                    }
+                    entered_border_with = None;
+                } else {
+                    entered_border_with = Some(cell.clone());
                 }
-                entered_border_with = None;
-            } else {
-                entered_border_with = Some(cell.clone());
             }
         }
     }
     count
 }
 
-fn main() {
+fn main() -> Result<()> {
     let input_path = "input/10.txt";
     let input = fs::read_to_string(input_path)
         .unwrap_or_else(|_| panic!("input file should be at {input_path}"));
     let start0 = Instant::now();
-    let matrix = parse(&input);
+    let matrix = parse(&input)?;
     let end0 = Instant::now();
     println!("input parsed in {:?}", end0.duration_since(start0));
 
@@ -236,11 +190,13 @@ fn main() {
     let end2 = Instant::now();
     println!("gold: {gold}");
     println!("took: {:?}", end2.duration_since(start2));
+    Ok(())
 }
 
 #[cfg(test)]
 mod day10 {
     use super::*;
+    use advent2023::geometry::interior_points;
 
     const SAMPLE1: &str = "-L|F7
 7S-7|
@@ -257,17 +213,19 @@ LJ.LJ
 ";
 
     #[test]
-    fn p1s1() {
-        let matrix = parse(SAMPLE1);
+    fn p1s1() -> Result<()> {
+        let matrix = parse(SAMPLE1)?;
         let result = p1(&matrix);
         assert_eq!(result, 4);
+        Ok(())
     }
 
     #[test]
-    fn p1s2() {
-        let matrix = parse(SAMPLE2);
+    fn p1s2() -> Result<()> {
+        let matrix = parse(SAMPLE2)?;
         let result = p1(&matrix);
         assert_eq!(result, 8);
+        Ok(())
     }
 
     const SAMPLE3: &str = "...........
@@ -305,23 +263,36 @@ L7JLJL-JLJLJL--JLJ.L
 ";
 
     #[test]
-    fn p2s3() {
-        let matrix = parse(SAMPLE3);
+    fn p2s3() -> Result<()> {
+        let matrix = parse(SAMPLE3)?;
         let result = p2(&matrix);
         assert_eq!(result, 4);
+        Ok(())
     }
 
     #[test]
-    fn p2s4() {
-        let matrix = parse(SAMPLE4);
+    fn p2s4() -> Result<()> {
+        let matrix = parse(SAMPLE4)?;
         let result = p2(&matrix);
         assert_eq!(result, 8);
+        Ok(())
     }
 
     #[test]
-    fn p2s5() {
-        let matrix = parse(SAMPLE5);
+    fn p2s5() -> Result<()> {
+        let matrix = parse(SAMPLE5)?;
         let result = p2(&matrix);
         assert_eq!(result, 10);
+        Ok(())
+    }
+
+    #[test]
+    fn p2_pick() -> Result<()> {
+        // The loop is a lattice polygon whose boundary points are its cells
+        for (sample, expected) in [(SAMPLE3, 4), (SAMPLE4, 8), (SAMPLE5, 10)] {
+            let pipe_loop = find_loop(&parse(sample)?).unwrap();
+            assert_eq!(interior_points(&pipe_loop), expected);
+        }
+        Ok(())
     }
 }
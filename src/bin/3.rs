@@ -1,5 +1,7 @@
 use advent2023::matrix::Matrix;
+use anyhow::Result;
 use std::collections::HashMap;
+use std::convert::Infallible;
 use std::time::Instant;
 use std::{env, fmt, fs};
 
@@ -20,51 +22,39 @@ impl fmt::Debug for Cell {
     }
 }
 
-fn parse(input: &str) -> Matrix<Cell> {
-    let width = input
-        .lines()
-        .next()
-        .expect("input should not be empty")
-        .chars()
-        .count();
-    let height = input.lines().count();
-    let mut items = Vec::new();
-    let mut buf = Vec::new();
-    let mut remember = Vec::new();
-    for c in input.chars().filter(|c| c.is_ascii_graphic()) {
-        if c.is_numeric() {
-            let n = c.to_digit(10).unwrap() as u64;
-            let cell = Cell::Num {
-                itself: n,
+fn parse(input: &str) -> Result<Matrix<Cell>> {
+    let mut matrix = Matrix::parse_grid(input, |c| {
+        Ok::<_, Infallible>(match c {
+            '.' => Cell::Empty,
+            c if c.is_ascii_digit() => Cell::Num {
+                itself: c.to_digit(10).unwrap() as u64,
                 whole: 0,
-                id: items.len() - remember.len(),
-            };
-            items.push(cell);
-            remember.push(items.len() - 1);
-            buf.push(n);
-        } else {
-            match c {
-                '.' => items.push(Cell::Empty),
-                x => items.push(Cell::Symbol(x)),
+                id: 0,
+            },
+            x => Cell::Symbol(x),
+        })
+    })?;
+    // Fill in the whole numbers, one row at a time so they never wrap around
+    let width = matrix.width();
+    for y in 0..matrix.height() {
+        let mut x = 0;
+        while x < width {
+            let start = x;
+            let mut num = 0;
+            while let Some(Cell::Num { itself, .. }) = matrix.get(x as isize, y as isize) {
+                num = num * 10 + itself;
+                x += 1;
             }
-            if !buf.is_empty() {
-                // Reset the state
-                let num = buf
-                    .iter()
-                    .rev()
-                    .enumerate()
-                    .fold(0, |n, (i, c)| n + *c * 10u64.pow(i as u32));
-                buf.clear();
-                for i in &remember {
-                    if let Cell::Num { ref mut whole, .. } = items[*i] {
-                        *whole = num
-                    }
+            for xx in start..x {
+                if let Cell::Num { whole, id, .. } = &mut matrix[(xx, y)] {
+                    *whole = num;
+                    *id = start + y * width;
                 }
-                remember.clear();
             }
+            x = x.max(start + 1);
         }
     }
-    Matrix::new(items, width, height)
+    Ok(matrix)
 }
 
 fn p1(matrix: &Matrix<Cell>) -> u64 {
@@ -74,8 +64,7 @@ fn p1(matrix: &Matrix<Cell>) -> u64 {
         if let Cell::Num { whole, id, .. } = cell {
             has_symbol |= matrix
                 .neighbor_indices(x, y)
-                .iter()
-                .any(|pos| matches!(matrix[*pos], Cell::Symbol(_)));
+                .any(|pos| matches!(matrix[pos], Cell::Symbol(_)));
             if has_symbol {
                 will_sum.insert(*id, *whole);
             }
@@ -93,9 +82,8 @@ fn p2(matrix: &Matrix<Cell>) -> u64 {
         .filter_map(|((x, y), _)| {
             let found: HashMap<_, _> = matrix
                 .neighbor_indices(x, y)
-                .iter()
                 .filter_map(|pos| {
-                    if let Cell::Num { whole, id, .. } = matrix[*pos] {
+                    if let Cell::Num { whole, id, .. } = matrix[pos] {
                         Some((id, whole))
                     } else {
                         None
@@ -103,7 +91,7 @@ fn p2(matrix: &Matrix<Cell>) -> u64 {
                 })
                 .collect();
             if found.len() == 2 {
-                Some(found.values().fold(1, |x, y| x * y))
+                Some(found.values().product::<u64>())
             } else {
                 None
             }
@@ -111,7 +99,7 @@ fn p2(matrix: &Matrix<Cell>) -> u64 {
         .sum()
 }
 
-fn main() {
+fn main() -> Result<()> {
     let input_path = {
         if env::args()
             .find(|s| matches!(s.as_str(), "--bigboy"))
@@ -128,7 +116,7 @@ fn main() {
     let input = fs::read_to_string(input_path)
         .unwrap_or_else(|_| panic!("input file should be at {input_path}"));
     let start0 = Instant::now();
-    let input = parse(&input);
+    let input = parse(&input)?;
     let end0 = Instant::now();
     println!("input parsed in {:?}", end0.duration_since(start0));
 
@@ -143,13 +131,14 @@ fn main() {
     let end2 = Instant::now();
     println!("gold: {gold}");
     println!("took: {:?}", end2.duration_since(start2));
+    Ok(())
 }
 
 #[cfg(test)]
 mod day3 {
     use super::*;
 
-    const SAMPLE1: &'static str = "467..114..
+    const SAMPLE1: &str = "467..114..
 ...*......
 ..35..633.
 ......#...
@@ -161,17 +150,19 @@ mod day3 {
 .664.598..";
 
     #[test]
-    fn sample1_p1() {
-        let input = parse(SAMPLE1);
+    fn sample1_p1() -> Result<()> {
+        let input = parse(SAMPLE1)?;
         assert_eq!(p1(&input), 4361);
+        Ok(())
     }
     #[test]
-    fn sample1_p2() {
-        let input = parse(SAMPLE1);
+    fn sample1_p2() -> Result<()> {
+        let input = parse(SAMPLE1)?;
         assert_eq!(p2(&input), 467835);
+        Ok(())
     }
 
-    const SAMPLE2: &'static str = "12.......*..
+    const SAMPLE2: &str = "12.......*..
 +.........34
 .......-12..
 ..78........
@@ -185,14 +176,15 @@ mod day3 {
 1.1..503+.56";
 
     #[test]
-    fn sample2_p1() {
-        let input = parse(SAMPLE2);
+    fn sample2_p1() -> Result<()> {
+        let input = parse(SAMPLE2)?;
         assert_eq!(p1(&input), 925);
+        Ok(())
     }
     #[test]
-    fn sample2_p2() {
-        let input = parse(SAMPLE2);
+    fn sample2_p2() -> Result<()> {
+        let input = parse(SAMPLE2)?;
         assert_eq!(p2(&input), 6756);
+        Ok(())
     }
 }
-
@@ -86,8 +86,9 @@ fn main() {
 #[cfg(test)]
 mod day4 {
     use super::*;
+    use advent2023::matrix::Matrix;
 
-    const SAMPLE: &'static str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+    const SAMPLE: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
 Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
 Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
 Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
@@ -105,4 +106,24 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
         let cards = parse(SAMPLE);
         assert_eq!(p2(&cards), 30);
     }
+
+    #[test]
+    fn sample2_matrix_power() {
+        // The copies x follow x = A x + 1, where A links each card to the cards
+        // it wins. A only points forward, so starting from 0 the fixed point is
+        // reached after len steps: the last column of [[A, 1], [0, 1]]^len.
+        let cards = parse(SAMPLE);
+        let n = cards.len();
+        let mut step = Matrix::new_default(n + 1, n + 1);
+        for (i, card) in cards.iter().enumerate() {
+            for j in (i + 1..=i + card.matching()).filter(|&j| j < n) {
+                step[(i, j)] = 1u64;
+            }
+            step[(n, i)] = 1;
+        }
+        step[(n, n)] = 1;
+        let copies = step.pow(n as u64);
+        let total: u64 = (0..n).map(|j| copies[(n, j)]).sum();
+        assert_eq!(total, p2(&cards) as u64);
+    }
 }
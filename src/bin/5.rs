@@ -188,7 +188,7 @@ fn main() {
 mod day5 {
     use super::*;
 
-    const SAMPLE: &'static str = "seeds: 79 14 55 13
+    const SAMPLE: &str = "seeds: 79 14 55 13
 
 seed-to-soil map:
 50 98 2
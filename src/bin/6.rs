@@ -100,7 +100,7 @@ fn main() {
 mod day6 {
     use super::*;
 
-    const SAMPLE: &'static str = "Time:      7  15   30
+    const SAMPLE: &str = "Time:      7  15   30
 Distance:  9  40  200";
 
     #[test]
@@ -83,7 +83,7 @@ fn main() {
 mod day9 {
     use super::*;
 
-    const SAMPLE: &'static str = "0 3 6 9 12 15
+    const SAMPLE: &str = "0 3 6 9 12 15
 1 3 6 10 15 21
 10 13 16 21 30 45";
 
@@ -99,4 +99,3 @@ mod day9 {
         assert_eq!(solve(&histories, true), 2);
     }
 }
-
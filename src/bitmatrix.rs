@@ -0,0 +1,382 @@
+use crate::matrix::{Matrix, MatrixError};
+use crate::pos::{Direction, Pos};
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops;
+
+/// A grid of booleans packed 64 to a word, each row starting on a fresh word.
+/// Bits past the width are always kept at 0.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BitMatrix {
+    words: Vec<u64>,
+    width: usize,
+    height: usize,
+    stride: usize,
+}
+
+impl BitMatrix {
+    /// All cells off.
+    pub fn new(width: usize, height: usize) -> Self {
+        let stride = width.div_ceil(64);
+        BitMatrix {
+            words: vec![0; stride * height],
+            width,
+            height,
+            stride,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn len(&self) -> usize {
+        self.width * self.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, x: isize, y: isize) -> Option<bool> {
+        let x = x.try_into().ok()?;
+        let y = y.try_into().ok()?;
+        self.try_get(x, y).ok()
+    }
+
+    pub fn get_pos(&self, pos: Pos) -> Option<bool> {
+        let (x, y) = pos.try_into().ok()?;
+        self.try_get(x, y).ok()
+    }
+
+    pub fn try_get(&self, x: usize, y: usize) -> Result<bool, MatrixError> {
+        let (word, bit) = self.locate(x, y)?;
+        Ok(self.words[word] >> bit & 1 == 1)
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: bool) {
+        self.try_set(x, y, value).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn set_pos(&mut self, pos: Pos, value: bool) {
+        let (x, y) = pos.try_into().unwrap_or_else(|_| {
+            panic!(
+                "Index {:?} out of range for BitMatrix with size ({}, {})",
+                pos, self.width, self.height
+            )
+        });
+        self.set(x, y, value)
+    }
+
+    pub fn try_set(&mut self, x: usize, y: usize, value: bool) -> Result<(), MatrixError> {
+        let (word, bit) = self.locate(x, y)?;
+        if value {
+            self.words[word] |= 1 << bit;
+        } else {
+            self.words[word] &= !(1 << bit);
+        }
+        Ok(())
+    }
+
+    /// Number of cells on.
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Positions of the cells on, row by row.
+    pub fn iter_ones(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.height).flat_map(move |y| {
+            self.row_words(y)
+                .iter()
+                .enumerate()
+                .flat_map(move |(i, &word)| {
+                    let mut word = word;
+                    std::iter::from_fn(move || {
+                        if word == 0 {
+                            return None;
+                        }
+                        let bit = word.trailing_zeros() as usize;
+                        word &= word - 1;
+                        Some((i * 64 + bit, y))
+                    })
+                })
+        })
+    }
+
+    /// The packed bits of row `y`, cell `x` being bit `x % 64` of word `x / 64`.
+    pub fn row_words(&self, y: usize) -> &[u64] {
+        if y >= self.height {
+            panic!(
+                "{}",
+                MatrixError::RowOutOfBounds {
+                    y,
+                    height: self.height
+                }
+            );
+        }
+        &self.words[y * self.stride..(y + 1) * self.stride]
+    }
+
+    /// A hash of row `y`, equal for equal rows.
+    pub fn row_hash(&self, y: usize) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.row_words(y).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Moves every cell one step towards `direction`, which must be one of
+    /// the 4 cardinal ones. Cells pushed over the edge are lost, and the
+    /// opposite edge is filled with off cells.
+    pub fn shift(&self, direction: Direction) -> BitMatrix {
+        let mut shifted = BitMatrix::new(self.width, self.height);
+        let stride = self.stride;
+        match direction {
+            Direction::North => {
+                shifted.words[..self.words.len().saturating_sub(stride)]
+                    .copy_from_slice(&self.words[stride.min(self.words.len())..]);
+            }
+            Direction::South => {
+                let len = self.words.len().saturating_sub(stride);
+                shifted.words[stride.min(self.words.len())..].copy_from_slice(&self.words[..len]);
+            }
+            Direction::East => {
+                for (old, new) in self
+                    .words
+                    .chunks(stride.max(1))
+                    .zip(shifted.words.chunks_mut(stride.max(1)))
+                {
+                    for i in 0..stride {
+                        let carry = if i > 0 { old[i - 1] >> 63 } else { 0 };
+                        new[i] = old[i] << 1 | carry;
+                    }
+                }
+                shifted.clear_padding();
+            }
+            Direction::West => {
+                for (old, new) in self
+                    .words
+                    .chunks(stride.max(1))
+                    .zip(shifted.words.chunks_mut(stride.max(1)))
+                {
+                    for i in 0..stride {
+                        let carry = if i + 1 < stride { old[i + 1] << 63 } else { 0 };
+                        new[i] = old[i] >> 1 | carry;
+                    }
+                }
+            }
+            d => panic!("Cannot shift a BitMatrix diagonally towards {d:?}"),
+        }
+        shifted
+    }
+
+    fn locate(&self, x: usize, y: usize) -> Result<(usize, usize), MatrixError> {
+        if x >= self.width || y >= self.height {
+            return Err(MatrixError::OutOfBounds {
+                x,
+                y,
+                width: self.width,
+                height: self.height,
+            });
+        }
+        Ok((y * self.stride + x / 64, x % 64))
+    }
+
+    fn clear_padding(&mut self) {
+        let used = self.width % 64;
+        if used == 0 {
+            return;
+        }
+        let mask = (1 << used) - 1;
+        for row in self.words.chunks_mut(self.stride) {
+            if let Some(last) = row.last_mut() {
+                *last &= mask;
+            }
+        }
+    }
+
+    fn combine<F>(&self, other: &BitMatrix, f: F) -> BitMatrix
+    where
+        F: Fn(u64, u64) -> u64,
+    {
+        if (self.width, self.height) != (other.width, other.height) {
+            panic!(
+                "{}",
+                MatrixError::ShapeMismatch {
+                    left: (self.width, self.height),
+                    right: (other.width, other.height),
+                }
+            );
+        }
+        BitMatrix {
+            words: self
+                .words
+                .iter()
+                .zip(&other.words)
+                .map(|(&a, &b)| f(a, b))
+                .collect(),
+            ..*self
+        }
+    }
+}
+
+impl From<&Matrix<bool>> for BitMatrix {
+    fn from(matrix: &Matrix<bool>) -> Self {
+        let mut bits = BitMatrix::new(matrix.width(), matrix.height());
+        for ((x, y), &b) in matrix.iter_pos() {
+            if b {
+                bits.set(x, y, true);
+            }
+        }
+        bits
+    }
+}
+
+impl From<&BitMatrix> for Matrix<bool> {
+    fn from(bits: &BitMatrix) -> Self {
+        let items = (0..bits.len()).map(|i| bits.try_get(i % bits.width, i / bits.width).unwrap());
+        Matrix::new(items, bits.width, bits.height)
+    }
+}
+
+impl ops::Index<(usize, usize)> for BitMatrix {
+    type Output = bool;
+
+    fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
+        match self.try_get(x, y) {
+            Ok(true) => &true,
+            Ok(false) => &false,
+            Err(e) => panic!("{e}"),
+        }
+    }
+}
+
+impl ops::Index<Pos> for BitMatrix {
+    type Output = bool;
+
+    fn index(&self, pos: Pos) -> &Self::Output {
+        match self.get_pos(pos) {
+            Some(true) => &true,
+            Some(false) => &false,
+            None => panic!(
+                "Index {:?} out of range for BitMatrix with size ({}, {})",
+                pos, self.width, self.height
+            ),
+        }
+    }
+}
+
+impl ops::BitAnd for &BitMatrix {
+    type Output = BitMatrix;
+
+    fn bitand(self, rhs: &BitMatrix) -> Self::Output {
+        self.combine(rhs, |a, b| a & b)
+    }
+}
+
+impl ops::BitOr for &BitMatrix {
+    type Output = BitMatrix;
+
+    fn bitor(self, rhs: &BitMatrix) -> Self::Output {
+        self.combine(rhs, |a, b| a | b)
+    }
+}
+
+impl ops::BitXor for &BitMatrix {
+    type Output = BitMatrix;
+
+    fn bitxor(self, rhs: &BitMatrix) -> Self::Output {
+        self.combine(rhs, |a, b| a ^ b)
+    }
+}
+
+impl ops::Not for &BitMatrix {
+    type Output = BitMatrix;
+
+    fn not(self) -> Self::Output {
+        let mut flipped = BitMatrix {
+            words: self.words.iter().map(|w| !w).collect(),
+            ..*self
+        };
+        flipped.clear_padding();
+        flipped
+    }
+}
+
+impl fmt::Debug for BitMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?}",
+            Matrix::from(self).map(|&b| if b { '#' } else { '.' })
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn bits(s: &str) -> BitMatrix {
+        let matrix = Matrix::parse_grid(s, |c| Ok::<_, ()>(c == '#')).unwrap();
+        BitMatrix::from(&matrix)
+    }
+
+    #[test]
+    fn test_bits() {
+        let mut b = bits("#..#\n.#..\n");
+        assert_eq!(b.count_ones(), 3);
+        assert!(b[(3, 0)]);
+        assert_eq!(b.get(4, 0), None);
+        b.set(2, 1, true);
+        assert_eq!(
+            b.iter_ones().collect::<Vec<_>>(),
+            vec![(0, 0), (3, 0), (1, 1), (2, 1)]
+        );
+        assert_eq!(
+            Matrix::from(&b),
+            Matrix::parse_grid("#..#\n.##.\n", |c| Ok::<_, ()>(c == '#')).unwrap()
+        );
+
+        let other = bits("##..\n..##\n");
+        assert_eq!(&b & &other, bits("#...\n..#.\n"));
+        assert_eq!(&b | &other, bits("##.#\n.###\n"));
+        assert_eq!(&b ^ &other, bits(".#.#\n.#.#\n"));
+        assert_eq!(!&b, bits(".##.\n#..#\n"));
+        assert_eq!((!&b).count_ones(), 4);
+    }
+
+    #[test]
+    fn test_shift_across_words() {
+        let width = 130;
+        let mut b = BitMatrix::new(width, 3);
+        b.set(63, 0, true);
+        b.set(129, 1, true);
+        b.set(0, 2, true);
+
+        let east = b.shift(Direction::East);
+        assert_eq!(east.iter_ones().collect::<Vec<_>>(), vec![(64, 0), (1, 2)]);
+        let west = b.shift(Direction::West);
+        assert_eq!(
+            west.iter_ones().collect::<Vec<_>>(),
+            vec![(62, 0), (128, 1)]
+        );
+        let north = b.shift(Direction::North);
+        assert_eq!(
+            north.iter_ones().collect::<Vec<_>>(),
+            vec![(129, 0), (0, 1)]
+        );
+        let south = b.shift(Direction::South);
+        assert_eq!(
+            south.iter_ones().collect::<Vec<_>>(),
+            vec![(63, 1), (129, 2)]
+        );
+
+        assert_eq!(b.row_hash(0), east.shift(Direction::West).row_hash(0));
+        assert_ne!(b.row_hash(0), b.row_hash(1));
+    }
+}
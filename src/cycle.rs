@@ -0,0 +1,180 @@
+/// Where a sequence `x0, step(x0), step(step(x0)), ...` starts repeating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// Index of the first state that is part of the cycle.
+    pub start: usize,
+    /// Length of the cycle.
+    pub period: usize,
+}
+
+impl Cycle {
+    /// Smallest index holding the same state as index `n`.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.period
+        }
+    }
+}
+
+/// Finds the cycle of the sequence generated by `step` from `initial`, with
+/// Brent's algorithm, keeping only two states around.
+///
+/// Never returns if the sequence does not repeat.
+pub fn find_cycle<S, F>(initial: &S, mut step: F) -> Cycle
+where
+    S: Clone + Eq,
+    F: FnMut(&S) -> S,
+{
+    // Find the period, doubling the distance between tortoise and hare
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(initial);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    // Walk both from the start, `period` apart, until they meet
+    let mut tortoise = initial.clone();
+    let mut hare = initial.clone();
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    Cycle { start, period }
+}
+
+/// The state after `n` steps, skipping over all the repetitions. Looks for
+/// the period like [`find_cycle`], but never steps more than `n` times
+/// before a repetition shows up, so it also returns on sequences that never
+/// repeat.
+pub fn state_at<S, F>(initial: &S, mut step: F, n: usize) -> S
+where
+    S: Clone + Eq,
+    F: FnMut(&S) -> S,
+{
+    if n == 0 {
+        return initial.clone();
+    }
+    // `hare` is the state after `taken` steps
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(initial);
+    let mut taken = 1;
+    while taken < n && tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        taken += 1;
+        period += 1;
+    }
+    // Past this point every state repeats `period` steps later
+    if taken < n {
+        for _ in 0..(n - taken) % period {
+            hare = step(&hare);
+        }
+    }
+    hare
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::matrix::Matrix;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_find_cycle() {
+        let step = |x: &u64| (x * x + 1) % 1009;
+        // Brute force, remembering every state
+        let mut seen = HashMap::new();
+        let mut x = 3;
+        let mut i = 0;
+        let expected = loop {
+            if let Some(first) = seen.insert(x, i) {
+                break Cycle {
+                    start: first,
+                    period: i - first,
+                };
+            }
+            x = step(&x);
+            i += 1;
+        };
+        assert_eq!(find_cycle(&3, step), expected);
+        assert!(expected.start > 0);
+
+        assert_eq!(
+            find_cycle(&7, |x| *x),
+            Cycle {
+                start: 0,
+                period: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_state_at() {
+        let matrix = Matrix::new(0..6, 3, 2);
+        let step = |m: &Matrix<i32>| m.clone().rotate_cw();
+        assert_eq!(
+            find_cycle(&matrix, step),
+            Cycle {
+                start: 0,
+                period: 4
+            }
+        );
+        let after = state_at(&matrix, step, 1_000_000_001);
+        assert_eq!(after, matrix.rotate_cw());
+
+        let cycle = Cycle {
+            start: 3,
+            period: 5,
+        };
+        assert_eq!(cycle.reduce(2), 2);
+        assert_eq!(cycle.reduce(9), 4);
+    }
+
+    #[test]
+    fn test_state_at_before_cycle() {
+        // Never repeats, but only a few steps are asked for
+        assert_eq!(state_at(&0u64, |x| x + 1, 5), 5);
+        assert_eq!(state_at(&0u64, |x| x + 1, 0), 0);
+
+        let step = |x: &u64| (x * x + 1) % 1009;
+        let cycle = find_cycle(&3, step);
+        let mut x = 3;
+        for n in 0..cycle.start + 3 * cycle.period {
+            let mut calls = 0;
+            let at = state_at(
+                &3,
+                |x| {
+                    calls += 1;
+                    step(x)
+                },
+                n,
+            );
+            assert_eq!(at, x);
+            if n <= cycle.start {
+                assert_eq!(calls, n);
+            }
+            x = step(&x);
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use crate::matrix::Matrix;
+use crate::pos::{Neighborhood, Pos};
+use std::collections::VecDeque;
+
+/// Result of [`Matrix::distance_transform`].
+#[derive(Debug, Clone)]
+pub struct DistanceField {
+    /// Steps to the nearest source, `None` where no source could reach.
+    pub distances: Matrix<Option<u32>>,
+    /// Index of the nearest source, in the order they were given. `None`
+    /// where no source could reach, or where several are equally near.
+    pub nearest: Matrix<Option<usize>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Label {
+    Unreached,
+    Source(usize),
+    Tie,
+}
+
+impl<T> Matrix<T> {
+    /// Breadth first distances from the closest of `sources`, moving by
+    /// `neighborhood` through cells that are `passable`. Sources need not be
+    /// passable themselves. Cells more than `max_radius` steps away are left
+    /// unreached.
+    pub fn distance_transform<F>(
+        &self,
+        sources: impl IntoIterator<Item = Pos>,
+        neighborhood: Neighborhood<'_>,
+        max_radius: Option<u32>,
+        passable: F,
+    ) -> DistanceField
+    where
+        F: Fn(&T) -> bool,
+    {
+        let mut distances: Matrix<Option<u32>> = Matrix::new_default(self.width(), self.height());
+        let mut labels = Matrix::new_with(self.width(), self.height(), || Label::Unreached);
+        let mut queue = VecDeque::new();
+        for (i, source) in sources.into_iter().enumerate() {
+            if self.contains(source) && distances[source].is_none() {
+                distances[source] = Some(0);
+                labels[source] = Label::Source(i);
+                queue.push_back(source);
+            }
+        }
+        while let Some(pos) = queue.pop_front() {
+            let d = distances[pos].unwrap();
+            if max_radius.is_some_and(|max| d >= max) {
+                continue;
+            }
+            for offset in neighborhood.offsets() {
+                let next = pos + offset;
+                if !self.get_pos(next).is_some_and(&passable) {
+                    continue;
+                }
+                match distances[next] {
+                    None => {
+                        distances[next] = Some(d + 1);
+                        labels[next] = labels[pos];
+                        queue.push_back(next);
+                    }
+                    // Reached by another source in the same number of steps
+                    Some(nd) if nd == d + 1 && labels[next] != labels[pos] => {
+                        labels[next] = Label::Tie;
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+        let nearest = labels.map(|label| match label {
+            Label::Source(i) => Some(*i),
+            Label::Unreached | Label::Tie => None,
+        });
+        DistanceField { distances, nearest }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const GRID: &str = "\
+a...#
+..#.#
+....b
+";
+
+    fn sources(matrix: &Matrix<char>) -> Vec<Pos> {
+        ['a', 'b']
+            .iter()
+            .map(|c| Pos::from(matrix.index_of(|x| x == c).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_rook_voronoi() {
+        let matrix = Matrix::parse_grid(GRID, Ok::<_, ()>).unwrap();
+        let field =
+            matrix.distance_transform(sources(&matrix), Neighborhood::Rook, None, |&c| c != '#');
+        let distances = field
+            .distances
+            .map(|d| d.map_or('#', |d| char::from(b'0' + d as u8)));
+        assert_eq!(
+            distances,
+            Matrix::parse_grid("0123#\n12#2#\n23210\n", Ok::<_, ()>).unwrap()
+        );
+        let owners = field.nearest.map(|n| match n {
+            Some(0) => 'a',
+            Some(_) => 'b',
+            None => '.',
+        });
+        assert_eq!(
+            owners,
+            Matrix::parse_grid("aaa..\naa.b.\na.bbb\n", Ok::<_, ()>).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_king_radius() {
+        let matrix = Matrix::parse_grid(GRID, Ok::<_, ()>).unwrap();
+        let field =
+            matrix.distance_transform([Pos::new(0, 0)], Neighborhood::King, Some(2), |&c| c != '#');
+        assert_eq!(field.distances[(2, 2)], Some(2));
+        assert_eq!(field.distances[(3, 1)], None);
+        assert_eq!(field.distances[(2, 1)], None);
+        assert_eq!(field.nearest[(1, 1)], Some(0));
+        let reached = field.distances.vec.iter().flatten().count();
+        assert_eq!(reached, 8);
+    }
+}
@@ -0,0 +1,174 @@
+//! Lattice polygons given as an ordered list of vertices, the last one
+//! joining back to the first. Every cell of a loop, like the one walked by a
+//! pipe, works just as well as its corners alone.
+//!
+//! Coordinates must lie strictly between `-2^62` and `2^62`, where every
+//! cross product and the area of any polygon not crossing itself fit in an
+//! `i128`.
+
+use crate::pos::Pos;
+
+/// Which points a polygon crossing itself encloses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillRule {
+    /// Inside when a ray from the point crosses the boundary an odd number
+    /// of times.
+    EvenOdd,
+    /// Inside when the boundary winds around the point at all.
+    NonZero,
+}
+
+/// Twice the signed shoelace area, which is always an integer. Positive when
+/// the vertices go clockwise on screen, with `y` pointing down.
+pub fn double_signed_area(vertices: &[Pos]) -> i128 {
+    // The partial sums can go out of range on the way to an area that is
+    // not, wrapping around gets back to it all the same
+    edges(vertices)
+        .map(|(a, b)| a.x as i128 * b.y as i128 - b.x as i128 * a.y as i128)
+        .fold(0, i128::wrapping_add)
+}
+
+/// Number of lattice points on the boundary, corners included.
+pub fn boundary_points(vertices: &[Pos]) -> u128 {
+    edges(vertices)
+        .map(|(a, b)| {
+            let dx = (b.x as i128 - a.x as i128).unsigned_abs();
+            let dy = (b.y as i128 - a.y as i128).unsigned_abs();
+            gcd(dx, dy)
+        })
+        .sum()
+}
+
+/// Number of lattice points strictly inside, by Pick's theorem. The polygon
+/// must not cross itself.
+pub fn interior_points(vertices: &[Pos]) -> u128 {
+    let area2 = double_signed_area(vertices).unsigned_abs();
+    (area2 + 2).saturating_sub(boundary_points(vertices)) / 2
+}
+
+/// How many times the boundary goes around `point`, clockwise on screen
+/// being positive like for [`double_signed_area`]. Meaningless for points on
+/// the boundary.
+pub fn winding_number(vertices: &[Pos], point: Pos) -> i64 {
+    let mut winding = 0;
+    for (a, b) in edges(vertices) {
+        if a.y <= point.y {
+            if b.y > point.y && cross(a, b, point) > 0 {
+                winding += 1;
+            }
+        } else if b.y <= point.y && cross(a, b, point) < 0 {
+            winding -= 1;
+        }
+    }
+    winding
+}
+
+/// Whether `point` is inside the polygon or on its boundary.
+pub fn contains_point(vertices: &[Pos], point: Pos, rule: FillRule) -> bool {
+    let on_boundary = edges(vertices).any(|(a, b)| {
+        cross(a, b, point) == 0
+            && a.x.min(b.x) <= point.x
+            && point.x <= a.x.max(b.x)
+            && a.y.min(b.y) <= point.y
+            && point.y <= a.y.max(b.y)
+    });
+    let winding = winding_number(vertices, point);
+    on_boundary
+        || match rule {
+            FillRule::EvenOdd => winding % 2 != 0,
+            FillRule::NonZero => winding != 0,
+        }
+}
+
+pub(crate) fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+fn edges(vertices: &[Pos]) -> impl Iterator<Item = (Pos, Pos)> + '_ {
+    vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(&a, &b)| (a, b))
+}
+
+/// Which side of the line through `a` and `b` the point `p` lies on.
+fn cross(a: Pos, b: Pos, p: Pos) -> i128 {
+    let (ax, ay) = (a.x as i128, a.y as i128);
+    (b.x as i128 - ax) * (p.y as i128 - ay) - (p.x as i128 - ax) * (b.y as i128 - ay)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_area_pick() {
+        let square = [
+            Pos::new(0, 0),
+            Pos::new(4, 0),
+            Pos::new(4, 4),
+            Pos::new(0, 4),
+        ];
+        assert_eq!(double_signed_area(&square), 32);
+        let mut reversed = square;
+        reversed.reverse();
+        assert_eq!(double_signed_area(&reversed), -32);
+        assert_eq!(boundary_points(&square), 16);
+        assert_eq!(interior_points(&square), 9);
+
+        let triangle = [Pos::new(0, 0), Pos::new(3, 0), Pos::new(0, 3)];
+        assert_eq!(double_signed_area(&triangle), 9);
+        assert_eq!(interior_points(&triangle), 1);
+
+        // Far apart corners would overflow i64 products
+        let huge = [
+            Pos::new(-(1 << 40), 0),
+            Pos::new(1 << 40, 0),
+            Pos::new(1 << 40, 1 << 40),
+        ];
+        assert_eq!(double_signed_area(&huge), 1 << 81);
+        assert_eq!(boundary_points(&huge), 1 << 42);
+
+        // As far out as coordinates go
+        let far = (1 << 62) - 1;
+        let widest = [
+            Pos::new(-far, -far),
+            Pos::new(far, -far),
+            Pos::new(far, far),
+            Pos::new(-far, far),
+        ];
+        let side = 2 * far as i128;
+        assert_eq!(double_signed_area(&widest), 2 * side * side);
+        assert_eq!(interior_points(&widest), ((side - 1) * (side - 1)) as u128);
+        for rule in [FillRule::EvenOdd, FillRule::NonZero] {
+            assert!(contains_point(&widest, Pos::new(0, 0), rule));
+            assert!(contains_point(&widest, Pos::new(far, 3), rule));
+        }
+    }
+
+    #[test]
+    fn test_contains_point() {
+        let square = [
+            Pos::new(0, 0),
+            Pos::new(4, 0),
+            Pos::new(4, 4),
+            Pos::new(0, 4),
+        ];
+        for rule in [FillRule::EvenOdd, FillRule::NonZero] {
+            assert!(contains_point(&square, Pos::new(2, 2), rule));
+            assert!(contains_point(&square, Pos::new(4, 2), rule));
+            assert!(!contains_point(&square, Pos::new(5, 2), rule));
+            assert!(!contains_point(&square, Pos::new(2, -1), rule));
+        }
+        assert_eq!(winding_number(&square, Pos::new(1, 3)), 1);
+
+        // Going around twice
+        let twice = [square, square].concat();
+        assert_eq!(winding_number(&twice, Pos::new(1, 3)), 2);
+        assert!(!contains_point(&twice, Pos::new(1, 3), FillRule::EvenOdd));
+        assert!(contains_point(&twice, Pos::new(1, 3), FillRule::NonZero));
+    }
+}
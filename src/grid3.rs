@@ -0,0 +1,433 @@
+use crate::matrix::Matrix;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+use std::ops;
+
+/// A cell of a [`Grid3`], as `(x, y, z)`.
+pub type Index3 = (usize, usize, usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Axis3 {
+    X,
+    Y,
+    Z,
+}
+
+const FACE_OFFSETS: [(isize, isize, isize); 6] = [
+    (0, 0, -1),
+    (0, -1, 0),
+    (-1, 0, 0),
+    (1, 0, 0),
+    (0, 1, 0),
+    (0, 0, 1),
+];
+
+/// A box of cells, stored x first, then y, then z. Each layer of constant z
+/// is laid out like a [`Matrix`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid3<T> {
+    pub vec: Vec<T>,
+    width: usize,
+    height: usize,
+    depth: usize,
+}
+
+impl<T> Grid3<T> {
+    pub fn new<I>(items: I, width: usize, height: usize, depth: usize) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        Self::try_new(items, width, height, depth).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_new<I>(
+        items: I,
+        width: usize,
+        height: usize,
+        depth: usize,
+    ) -> Result<Self, Grid3Error>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let vec: Vec<T> = items.into_iter().collect();
+        if vec.len() != width * height * depth {
+            return Err(Grid3Error::SizeMismatch {
+                len: vec.len(),
+                size: (width, height, depth),
+            });
+        }
+        Ok(Self {
+            vec,
+            width,
+            height,
+            depth,
+        })
+    }
+
+    pub fn new_default(width: usize, height: usize, depth: usize) -> Self
+    where
+        T: Default,
+    {
+        Self::new_with(width, height, depth, T::default)
+    }
+
+    pub fn new_with<F>(width: usize, height: usize, depth: usize, f: F) -> Self
+    where
+        F: Fn() -> T,
+    {
+        Grid3 {
+            vec: std::iter::repeat_with(f)
+                .take(width * height * depth)
+                .collect(),
+            width,
+            height,
+            depth,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn size(&self) -> Index3 {
+        (self.width, self.height, self.depth)
+    }
+
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    pub fn get(&self, x: isize, y: isize, z: isize) -> Option<&T> {
+        let x = x.try_into().ok()?;
+        let y = y.try_into().ok()?;
+        let z = z.try_into().ok()?;
+        self.try_get((x, y, z)).ok()
+    }
+
+    pub fn get_mut(&mut self, x: isize, y: isize, z: isize) -> Option<&mut T> {
+        let x = x.try_into().ok()?;
+        let y = y.try_into().ok()?;
+        let z = z.try_into().ok()?;
+        self.try_get_mut((x, y, z)).ok()
+    }
+
+    pub fn try_get(&self, index: Index3) -> Result<&T, Grid3Error> {
+        let idx = self.checked_index(index)?;
+        Ok(&self.vec[idx])
+    }
+
+    pub fn try_get_mut(&mut self, index: Index3) -> Result<&mut T, Grid3Error> {
+        let idx = self.checked_index(index)?;
+        Ok(&mut self.vec[idx])
+    }
+
+    fn checked_index(&self, (x, y, z): Index3) -> Result<usize, Grid3Error> {
+        if x >= self.width || y >= self.height || z >= self.depth {
+            return Err(Grid3Error::OutOfBounds {
+                index: (x, y, z),
+                size: self.size(),
+            });
+        }
+        Ok(x + (y + z * self.height) * self.width)
+    }
+
+    pub fn iter_pos(&self) -> impl Iterator<Item = (Index3, &T)> {
+        let (width, height) = (self.width, self.height);
+        self.vec.iter().enumerate().map(move |(i, t)| {
+            let (x, rest) = (i % width, i / width);
+            ((x, rest % height, rest / height), t)
+        })
+    }
+
+    /// The up to 6 cells sharing a face with `index`.
+    pub fn face_neighbor_indices(&self, index: Index3) -> impl Iterator<Item = Index3> + '_ {
+        FACE_OFFSETS
+            .into_iter()
+            .filter_map(move |offset| self.offset(index, offset))
+    }
+
+    /// The up to 26 cells sharing a face, an edge or a corner with `index`.
+    pub fn neighbor_indices(&self, index: Index3) -> impl Iterator<Item = Index3> + '_ {
+        (-1..=1)
+            .flat_map(|dz| (-1..=1).flat_map(move |dy| (-1..=1).map(move |dx| (dx, dy, dz))))
+            .filter(|&offset| offset != (0, 0, 0))
+            .filter_map(move |offset| self.offset(index, offset))
+    }
+
+    fn offset(&self, (x, y, z): Index3, (dx, dy, dz): (isize, isize, isize)) -> Option<Index3> {
+        let x = x.checked_add_signed(dx).filter(|&x| x < self.width)?;
+        let y = y.checked_add_signed(dy).filter(|&y| y < self.height)?;
+        let z = z.checked_add_signed(dz).filter(|&z| z < self.depth)?;
+        Some((x, y, z))
+    }
+
+    /// The layer at `index` across `axis`. Cutting across `Z` keeps `(x, y)`,
+    /// across `Y` gives `(x, z)` and across `X` gives `(y, z)`.
+    pub fn slice(&self, axis: Axis3, index: usize) -> Matrix<T>
+    where
+        T: Clone,
+    {
+        self.try_slice(axis, index)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_slice(&self, axis: Axis3, index: usize) -> Result<Matrix<T>, Grid3Error>
+    where
+        T: Clone,
+    {
+        let (len, width, height) = match axis {
+            Axis3::X => (self.width, self.height, self.depth),
+            Axis3::Y => (self.height, self.width, self.depth),
+            Axis3::Z => (self.depth, self.width, self.height),
+        };
+        if index >= len {
+            return Err(Grid3Error::LayerOutOfBounds { axis, index, len });
+        }
+        let items = (0..height).flat_map(|v| {
+            (0..width).map(move |u| match axis {
+                Axis3::X => (index, u, v),
+                Axis3::Y => (u, index, v),
+                Axis3::Z => (u, v, index),
+            })
+        });
+        Ok(Matrix::new(
+            items.map(|i| self.vec[self.checked_index(i).unwrap()].clone()),
+            width,
+            height,
+        ))
+    }
+
+    /// Inclusive corners of the smallest box holding every cell matching
+    /// `predicate`, `None` if there are none.
+    pub fn bounding_box<F>(&self, mut predicate: F) -> Option<(Index3, Index3)>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.iter_pos()
+            .filter(|(_, t)| predicate(t))
+            .map(|(i, _)| i)
+            .fold(None, |acc, (x, y, z)| {
+                let ((x0, y0, z0), (x1, y1, z1)) = acc.unwrap_or(((x, y, z), (x, y, z)));
+                Some((
+                    (x0.min(x), y0.min(y), z0.min(z)),
+                    (x1.max(x), y1.max(y), z1.max(z)),
+                ))
+            })
+    }
+
+    /// A copy of the box between the inclusive corners `min` and `max`.
+    pub fn crop(&self, min: Index3, max: Index3) -> Grid3<T>
+    where
+        T: Clone,
+    {
+        self.try_crop(min, max).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_crop(&self, min: Index3, max: Index3) -> Result<Grid3<T>, Grid3Error>
+    where
+        T: Clone,
+    {
+        if min.0 > max.0
+            || min.1 > max.1
+            || min.2 > max.2
+            || max.0 >= self.width
+            || max.1 >= self.height
+            || max.2 >= self.depth
+        {
+            return Err(Grid3Error::BoxOutOfBounds {
+                min,
+                max,
+                size: self.size(),
+            });
+        }
+        let items = (min.2..=max.2).flat_map(|z| {
+            (min.1..=max.1).flat_map(move |y| (min.0..=max.0).map(move |x| (x, y, z)))
+        });
+        Ok(Grid3 {
+            vec: items.map(|i| self[i].clone()).collect(),
+            width: max.0 - min.0 + 1,
+            height: max.1 - min.1 + 1,
+            depth: max.2 - min.2 + 1,
+        })
+    }
+
+    /// Every cell reachable from `start` through shared faces, without
+    /// leaving the cells matching `predicate`. Empty if `start` itself does
+    /// not match.
+    pub fn flood_fill<F>(&self, start: Index3, predicate: F) -> Vec<Index3>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let mut members = Vec::new();
+        if !self.try_get(start).is_ok_and(&predicate) {
+            return members;
+        }
+        let mut seen = vec![false; self.len()];
+        let mut queue = VecDeque::from([start]);
+        seen[self.checked_index(start).unwrap()] = true;
+        while let Some(index) = queue.pop_front() {
+            members.push(index);
+            for next in self.face_neighbor_indices(index) {
+                let idx = self.checked_index(next).unwrap();
+                if !seen[idx] && predicate(&self.vec[idx]) {
+                    seen[idx] = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+        members
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Grid3Error {
+    /// `len` items cannot fill a grid of `size`.
+    SizeMismatch {
+        len: usize,
+        size: Index3,
+    },
+    OutOfBounds {
+        index: Index3,
+        size: Index3,
+    },
+    /// The box between the inclusive corners `min` and `max` is empty or
+    /// does not fit in a grid of `size`.
+    BoxOutOfBounds {
+        min: Index3,
+        max: Index3,
+        size: Index3,
+    },
+    /// Layer `index` across `axis`, along which the grid is only `len` long.
+    LayerOutOfBounds {
+        axis: Axis3,
+        index: usize,
+        len: usize,
+    },
+}
+
+impl fmt::Display for Grid3Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Grid3Error::SizeMismatch { len, size } => {
+                write!(f, "{len} items cannot fill a Grid3 with size {size:?}")
+            }
+            Grid3Error::OutOfBounds { index, size } => write!(
+                f,
+                "Index {index:?} out of range for Grid3 with size {size:?}"
+            ),
+            Grid3Error::BoxOutOfBounds { min, max, size } => write!(
+                f,
+                "Box from {min:?} to {max:?} out of range for Grid3 with size {size:?}"
+            ),
+            Grid3Error::LayerOutOfBounds { axis, index, len } => write!(
+                f,
+                "Layer {index} across {axis:?} out of range for Grid3 with length {len}"
+            ),
+        }
+    }
+}
+
+impl Error for Grid3Error {}
+
+impl<T> ops::Index<Index3> for Grid3<T> {
+    type Output = T;
+
+    fn index(&self, index: Index3) -> &Self::Output {
+        self.try_get(index).unwrap_or_else(|e| panic!("{e}"))
+    }
+}
+
+impl<T> ops::IndexMut<Index3> for Grid3<T> {
+    fn index_mut(&mut self, index: Index3) -> &mut Self::Output {
+        self.try_get_mut(index).unwrap_or_else(|e| panic!("{e}"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_indexing_neighbors() {
+        let grid = Grid3::new(0..24, 2, 3, 4);
+        assert_eq!(grid[(1, 2, 3)], 23);
+        assert_eq!(grid[(1, 0, 1)], 7);
+        assert_eq!(grid.get(0, -1, 0), None);
+        assert!(matches!(
+            grid.try_get((2, 0, 0)),
+            Err(Grid3Error::OutOfBounds { .. })
+        ));
+        assert!(grid.iter_pos().all(|(i, &t)| grid[i] == t));
+
+        assert_eq!(grid.face_neighbor_indices((0, 0, 0)).count(), 3);
+        assert_eq!(grid.face_neighbor_indices((1, 1, 1)).count(), 5);
+        assert_eq!(grid.neighbor_indices((0, 0, 0)).count(), 7);
+        assert_eq!(grid.neighbor_indices((1, 1, 1)).count(), 17);
+        let big = Grid3::<u8>::new_default(3, 3, 3);
+        assert_eq!(big.neighbor_indices((1, 1, 1)).count(), 26);
+        assert_eq!(big.face_neighbor_indices((1, 1, 1)).count(), 6);
+    }
+
+    #[test]
+    fn test_slice_crop() {
+        let grid = Grid3::new(0..24, 2, 3, 4);
+        assert_eq!(grid.slice(Axis3::Z, 1), Matrix::new(6..12, 2, 3));
+        assert_eq!(
+            grid.slice(Axis3::Y, 2),
+            Matrix::new([4, 5, 10, 11, 16, 17, 22, 23], 2, 4)
+        );
+        let x = grid.slice(Axis3::X, 1);
+        assert_eq!((x.width(), x.height()), (3, 4));
+        assert_eq!(x[(2, 3)], grid[(1, 2, 3)]);
+        assert_eq!(
+            grid.try_slice(Axis3::X, 2),
+            Err(Grid3Error::LayerOutOfBounds {
+                axis: Axis3::X,
+                index: 2,
+                len: 2
+            })
+        );
+        assert_eq!(
+            Grid3::try_new(0..23, 2, 3, 4),
+            Err(Grid3Error::SizeMismatch {
+                len: 23,
+                size: (2, 3, 4)
+            })
+        );
+
+        let bbox = grid.bounding_box(|&t| t == 9 || t == 14).unwrap();
+        assert_eq!(bbox, ((0, 1, 1), (1, 1, 2)));
+        let cropped = grid.crop(bbox.0, bbox.1);
+        assert_eq!(cropped, Grid3::new([8, 9, 14, 15], 2, 1, 2));
+        assert!(grid.try_crop((1, 0, 0), (0, 0, 0)).is_err());
+        assert_eq!(grid.bounding_box(|&t| t > 100), None);
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        // A wall at x = 1 splits the cube, except for a hole at the far corner
+        let mut grid = Grid3::new_with(3, 3, 3, || true);
+        for y in 0..3 {
+            for z in 0..3 {
+                grid[(1, y, z)] = false;
+            }
+        }
+        assert_eq!(grid.flood_fill((0, 0, 0), |&open| open).len(), 9);
+        grid[(1, 2, 2)] = true;
+        assert_eq!(grid.flood_fill((0, 0, 0), |&open| open).len(), 19);
+        assert!(grid.flood_fill((1, 0, 0), |&open| open).is_empty());
+    }
+}
@@ -0,0 +1,362 @@
+use crate::matrix::ParseGridError;
+use crate::search::{self, Search};
+use std::collections::HashMap;
+use std::fmt;
+use std::ops;
+
+/// A hexagon in axial coordinates. The third cube coordinate is
+/// `s = -q - r`, see [`Hex::s`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Hex {
+    pub q: i64,
+    pub r: i64,
+}
+
+impl Hex {
+    pub const ORIGIN: Hex = Hex { q: 0, r: 0 };
+
+    /// The 6 unit steps, counter-clockwise starting east for pointy-top
+    /// hexagons.
+    pub const DIRECTIONS: [Hex; 6] = [
+        Hex::new(1, 0),
+        Hex::new(1, -1),
+        Hex::new(0, -1),
+        Hex::new(-1, 0),
+        Hex::new(-1, 1),
+        Hex::new(0, 1),
+    ];
+
+    pub const fn new(q: i64, r: i64) -> Self {
+        Hex { q, r }
+    }
+
+    /// Builds a hexagon from its cube coordinates, which must sum to 0.
+    pub fn from_cube(q: i64, r: i64, s: i64) -> Self {
+        assert_eq!(q + r + s, 0, "cube coordinates must sum to 0");
+        Hex::new(q, r)
+    }
+
+    pub fn s(self) -> i64 {
+        -self.q - self.r
+    }
+
+    pub fn distance(self, other: Hex) -> u64 {
+        let d = self - other;
+        (d.q.unsigned_abs() + d.r.unsigned_abs() + d.s().unsigned_abs()) / 2
+    }
+
+    pub fn neighbors(self) -> impl Iterator<Item = Hex> {
+        Hex::DIRECTIONS.into_iter().map(move |d| self + d)
+    }
+
+    /// Rotates a sixth of a turn clockwise around `center`.
+    pub fn rotate_cw(self, center: Hex) -> Hex {
+        let d = self - center;
+        center + Hex::from_cube(-d.r, -d.s(), -d.q)
+    }
+
+    /// Rotates a sixth of a turn counter-clockwise around `center`.
+    pub fn rotate_ccw(self, center: Hex) -> Hex {
+        let d = self - center;
+        center + Hex::from_cube(-d.s(), -d.q, -d.r)
+    }
+
+    /// The hexagons exactly `radius` steps away, going around once. Just
+    /// `self` for a radius of 0.
+    pub fn ring(self, radius: u32) -> impl Iterator<Item = Hex> {
+        let radius = radius as i64;
+        let center = (radius == 0).then_some(self);
+        let mut corner = self + Hex::DIRECTIONS[4] * radius;
+        let sides = (0..6).flat_map(move |side| {
+            let start = corner;
+            corner = corner + Hex::DIRECTIONS[side] * radius;
+            (0..radius).map(move |step| start + Hex::DIRECTIONS[side] * step)
+        });
+        center.into_iter().chain(sides.take(6 * radius as usize))
+    }
+
+    /// The hexagons at most `radius` steps away, ring by ring from `self`.
+    pub fn spiral(self, radius: u32) -> impl Iterator<Item = Hex> {
+        (0..=radius).flat_map(move |r| self.ring(r))
+    }
+
+    /// Converts from column and row in an offset layout.
+    pub fn from_offset(col: i64, row: i64, layout: OffsetLayout) -> Self {
+        match layout {
+            OffsetLayout::OddR => Hex::new(col - (row - (row & 1)) / 2, row),
+            OffsetLayout::EvenR => Hex::new(col - (row + (row & 1)) / 2, row),
+            OffsetLayout::OddQ => Hex::new(col, row - (col - (col & 1)) / 2),
+            OffsetLayout::EvenQ => Hex::new(col, row - (col + (col & 1)) / 2),
+        }
+    }
+
+    /// Converts to `(col, row)` in an offset layout.
+    pub fn to_offset(self, layout: OffsetLayout) -> (i64, i64) {
+        let Hex { q, r } = self;
+        match layout {
+            OffsetLayout::OddR => (q + (r - (r & 1)) / 2, r),
+            OffsetLayout::EvenR => (q + (r + (r & 1)) / 2, r),
+            OffsetLayout::OddQ => (q, r + (q - (q & 1)) / 2),
+            OffsetLayout::EvenQ => (q, r + (q + (q & 1)) / 2),
+        }
+    }
+}
+
+impl fmt::Debug for Hex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.q, self.r)
+    }
+}
+
+impl ops::Add for Hex {
+    type Output = Hex;
+
+    fn add(self, rhs: Hex) -> Self::Output {
+        Hex::new(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+
+impl ops::Sub for Hex {
+    type Output = Hex;
+
+    fn sub(self, rhs: Hex) -> Self::Output {
+        Hex::new(self.q - rhs.q, self.r - rhs.r)
+    }
+}
+
+impl ops::Mul<i64> for Hex {
+    type Output = Hex;
+
+    fn mul(self, rhs: i64) -> Self::Output {
+        Hex::new(self.q * rhs, self.r * rhs)
+    }
+}
+
+/// How rows or columns of hexagons are laid out as text. With `OddR` every
+/// odd row is pushed half a hexagon to the right, with `EvenR` every even
+/// row, and `OddQ` and `EvenQ` do the same with flat-top columns pushed down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OffsetLayout {
+    OddR,
+    EvenR,
+    OddQ,
+    EvenQ,
+}
+
+/// A grid of hexagons of any shape.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HexGrid<T> {
+    cells: HashMap<Hex, T>,
+}
+
+impl<T> Default for HexGrid<T> {
+    fn default() -> Self {
+        HexGrid::new()
+    }
+}
+
+impl<T> HexGrid<T> {
+    pub fn new() -> Self {
+        HexGrid {
+            cells: HashMap::new(),
+        }
+    }
+
+    /// Parses text where each non-whitespace char is a hexagon, the spaces
+    /// only serving to show the offset rows, like
+    ///
+    /// ```text
+    /// a b c
+    ///  d e f
+    /// g h i
+    /// ```
+    ///
+    /// Columns are counted in hexagons, not chars.
+    pub fn parse<F, E>(
+        input: &str,
+        layout: OffsetLayout,
+        mut f: F,
+    ) -> Result<Self, ParseGridError<E>>
+    where
+        F: FnMut(char) -> Result<T, E>,
+    {
+        let mut grid = HexGrid::new();
+        for (row, line) in input.lines().enumerate() {
+            for (col, ch) in line.chars().filter(|c| !c.is_whitespace()).enumerate() {
+                let item = f(ch).map_err(|error| ParseGridError::Cell {
+                    row,
+                    col,
+                    ch,
+                    error,
+                })?;
+                grid.insert(Hex::from_offset(col as i64, row as i64, layout), item);
+            }
+        }
+        Ok(grid)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, hex: Hex) -> bool {
+        self.cells.contains_key(&hex)
+    }
+
+    pub fn get(&self, hex: Hex) -> Option<&T> {
+        self.cells.get(&hex)
+    }
+
+    pub fn get_mut(&mut self, hex: Hex) -> Option<&mut T> {
+        self.cells.get_mut(&hex)
+    }
+
+    pub fn insert(&mut self, hex: Hex, value: T) -> Option<T> {
+        self.cells.insert(hex, value)
+    }
+
+    /// The cells of the grid, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Hex, &T)> {
+        self.cells.iter().map(|(hex, t)| (*hex, t))
+    }
+
+    /// The neighbours of `hex` that are part of the grid.
+    pub fn neighbors(&self, hex: Hex) -> impl Iterator<Item = (Hex, &T)> {
+        hex.neighbors()
+            .filter_map(move |n| self.get(n).map(|t| (n, t)))
+    }
+
+    /// Every hexagon reachable from `start` without leaving the cells
+    /// matching `predicate`.
+    pub fn flood_fill<F>(&self, start: Hex, predicate: F) -> Vec<Hex>
+    where
+        F: Fn(&T) -> bool,
+    {
+        if !self.get(start).is_some_and(&predicate) {
+            return Vec::new();
+        }
+        self.bfs(start, predicate)
+            .distances()
+            .keys()
+            .copied()
+            .collect()
+    }
+
+    /// Distances from `start` through hexagons that are `passable`.
+    pub fn bfs<F>(&self, start: Hex, passable: F) -> Search<Hex>
+    where
+        F: Fn(&T) -> bool,
+    {
+        search::bfs(
+            [start],
+            |&hex| {
+                self.neighbors(hex)
+                    .filter(|(_, t)| passable(t))
+                    .map(|(n, _)| n)
+                    .collect::<Vec<_>>()
+            },
+            |_| false,
+        )
+    }
+}
+
+impl<T> ops::Index<Hex> for HexGrid<T> {
+    type Output = T;
+
+    fn index(&self, hex: Hex) -> &Self::Output {
+        self.get(hex)
+            .unwrap_or_else(|| panic!("Hexagon {hex:?} is not part of the HexGrid"))
+    }
+}
+
+impl<T> ops::IndexMut<Hex> for HexGrid<T> {
+    fn index_mut(&mut self, hex: Hex) -> &mut Self::Output {
+        self.get_mut(hex)
+            .unwrap_or_else(|| panic!("Hexagon {hex:?} is not part of the HexGrid"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_hex_geometry() {
+        let a = Hex::new(1, -3);
+        assert_eq!(a.s(), 2);
+        assert_eq!(a.distance(Hex::ORIGIN), 3);
+        assert!(a.neighbors().all(|n| n.distance(a) == 1));
+
+        let center = Hex::new(2, 2);
+        assert_eq!(Hex::new(3, 2).rotate_cw(center), Hex::new(2, 3));
+        assert_eq!(Hex::new(2, 3).rotate_ccw(center), Hex::new(3, 2));
+        let mut h = a;
+        for _ in 0..6 {
+            h = h.rotate_cw(center);
+            assert_eq!(h.distance(center), a.distance(center));
+        }
+        assert_eq!(h, a);
+
+        assert_eq!(center.ring(0).collect::<Vec<_>>(), vec![center]);
+        for r in 1..4 {
+            let ring: Vec<_> = center.ring(r).collect();
+            assert_eq!(ring.len(), 6 * r as usize);
+            assert!(ring.iter().all(|h| h.distance(center) == r as u64));
+        }
+        let mut spiral: Vec<_> = center.spiral(3).collect();
+        assert_eq!(spiral.len(), 1 + 3 * 3 * 4);
+        spiral.sort();
+        spiral.dedup();
+        assert_eq!(spiral.len(), 37);
+    }
+
+    #[test]
+    fn test_offset_roundtrip() {
+        for layout in [
+            OffsetLayout::OddR,
+            OffsetLayout::EvenR,
+            OffsetLayout::OddQ,
+            OffsetLayout::EvenQ,
+        ] {
+            for col in -3..3 {
+                for row in -3..3 {
+                    let hex = Hex::from_offset(col, row, layout);
+                    assert_eq!(hex.to_offset(layout), (col, row));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_and_search() {
+        // The wall cuts the four open cells on the left off from the rest
+        let input = "\
+. . # .
+ . # . .
+. # . .
+";
+        let grid = HexGrid::parse(input, OffsetLayout::OddR, |c| Ok::<_, ()>(c == '.')).unwrap();
+        assert_eq!(grid.len(), 12);
+        let start = Hex::from_offset(0, 0, OffsetLayout::OddR);
+        let end = Hex::from_offset(3, 2, OffsetLayout::OddR);
+        let filled = grid.flood_fill(start, |&open| open);
+        assert_eq!(filled.len(), 4);
+        assert!(!filled.contains(&end));
+
+        let search = grid.bfs(Hex::from_offset(3, 0, OffsetLayout::OddR), |&open| open);
+        assert_eq!(search.distance(&end), Some(2));
+
+        let err = HexGrid::parse("a b\n", OffsetLayout::OddR, |c| match c {
+            'a' => Ok(()),
+            _ => Err(c),
+        });
+        assert!(matches!(
+            err,
+            Err(ParseGridError::Cell { row: 0, col: 1, .. })
+        ));
+    }
+}
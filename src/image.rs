@@ -0,0 +1,84 @@
+use crate::matrix::Matrix;
+use std::io::{self, Write};
+
+impl<T> Matrix<T> {
+    /// Writes a binary PPM (P6) image, each cell drawn as a `scale` by `scale`
+    /// square of the colour `rgb` gives it.
+    pub fn write_ppm<W, F>(&self, out: W, scale: usize, rgb: F) -> io::Result<()>
+    where
+        W: Write,
+        F: Fn(&T) -> [u8; 3],
+    {
+        self.write_netpbm(out, "P6", scale, rgb)
+    }
+
+    /// Writes a binary PGM (P5) image, each cell drawn as a `scale` by `scale`
+    /// square of the gray level `gray` gives it.
+    pub fn write_pgm<W, F>(&self, out: W, scale: usize, gray: F) -> io::Result<()>
+    where
+        W: Write,
+        F: Fn(&T) -> u8,
+    {
+        self.write_netpbm(out, "P5", scale, |t| [gray(t)])
+    }
+
+    fn write_netpbm<W, F, const N: usize>(
+        &self,
+        out: W,
+        magic: &str,
+        scale: usize,
+        pixel: F,
+    ) -> io::Result<()>
+    where
+        W: Write,
+        F: Fn(&T) -> [u8; N],
+    {
+        let mut out = io::BufWriter::new(out);
+        writeln!(
+            out,
+            "{magic}\n{} {}\n255",
+            self.width() * scale,
+            self.height() * scale
+        )?;
+        let mut line = Vec::with_capacity(self.width() * scale * N);
+        for row in self.rows() {
+            line.clear();
+            for item in row {
+                let px = pixel(item);
+                for _ in 0..scale {
+                    line.extend_from_slice(&px);
+                }
+            }
+            for _ in 0..scale {
+                out.write_all(&line)?;
+            }
+        }
+        out.flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_write_ppm() {
+        let matrix = Matrix::new([true, false], 2, 1);
+        let mut out = Vec::new();
+        matrix
+            .write_ppm(&mut out, 2, |&b| if b { [255, 0, 0] } else { [0, 0, 0] })
+            .unwrap();
+        let header = b"P6\n4 2\n255\n";
+        assert_eq!(&out[..header.len()], header);
+        let red_black = [255, 0, 0, 255, 0, 0, 0, 0, 0, 0, 0, 0];
+        assert_eq!(out[header.len()..], [red_black, red_black].concat());
+    }
+
+    #[test]
+    fn test_write_pgm() {
+        let matrix = Matrix::new(0..4u8, 2, 2);
+        let mut out = Vec::new();
+        matrix.write_pgm(&mut out, 1, |&n| n * 80).unwrap();
+        assert_eq!(out, b"P5\n2 2\n255\n\x00\x50\xa0\xf0");
+    }
+}
@@ -1 +1,20 @@
+pub mod automaton;
+pub mod bitmatrix;
+pub mod cycle;
+pub mod distance;
+pub mod geometry;
+pub mod grid3;
+pub mod hex;
+pub mod image;
+pub mod linalg;
 pub mod matrix;
+pub mod pattern;
+pub mod pos;
+pub mod ray;
+pub mod reflection;
+pub mod region;
+pub mod render;
+pub mod search;
+pub mod sparse;
+pub mod view;
+pub mod wrap;
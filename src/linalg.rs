@@ -0,0 +1,199 @@
+use crate::matrix::{Matrix, MatrixError};
+use std::ops;
+
+/// The integer types matrix arithmetic works with.
+pub trait Num:
+    Copy
+    + ops::Add<Output = Self>
+    + ops::Sub<Output = Self>
+    + ops::Mul<Output = Self>
+    + ops::Rem<Output = Self>
+    + PartialEq
+{
+    const ZERO: Self;
+    const ONE: Self;
+}
+
+macro_rules! impl_num {
+    ($($t:ty),*) => {
+        $(impl Num for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+        })*
+    };
+}
+
+impl_num!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl<T: Num> Matrix<T> {
+    pub fn identity(n: usize) -> Self {
+        let items = (0..n * n).map(|i| if i % n == i / n { T::ONE } else { T::ZERO });
+        Matrix::new(items, n, n)
+    }
+
+    /// The matrix product `self × other`.
+    pub fn matmul(&self, other: &Matrix<T>) -> Matrix<T> {
+        self.try_matmul(other).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_matmul(&self, other: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+        self.matmul_with(other, |n| n)
+    }
+
+    /// `self` raised to the power `exp` by repeated squaring. Only square
+    /// matrices have powers.
+    pub fn pow(&self, exp: u64) -> Matrix<T> {
+        self.try_pow(exp).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_pow(&self, exp: u64) -> Result<Matrix<T>, MatrixError> {
+        self.pow_with(exp, |n| n)
+    }
+
+    /// Like [`Matrix::pow`], with every cell taken modulo `m` along the way.
+    /// Products of two cells are taken before reducing them, so nothing
+    /// overflows only as long as `(m - 1)²` fits in `T`: up to `m = 2³²` for
+    /// `u64` cells with no negative ones.
+    pub fn pow_mod(&self, exp: u64, m: T) -> Matrix<T> {
+        self.try_pow_mod(exp, m).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_pow_mod(&self, exp: u64, m: T) -> Result<Matrix<T>, MatrixError> {
+        if m == T::ZERO {
+            return Err(MatrixError::ZeroModulus);
+        }
+        self.pow_with(exp, |n| n % m)
+    }
+
+    fn pow_with<R>(&self, mut exp: u64, reduce: R) -> Result<Matrix<T>, MatrixError>
+    where
+        R: Fn(T) -> T + Copy,
+    {
+        if self.width() != self.height() {
+            return Err(MatrixError::NotSquare {
+                width: self.width(),
+                height: self.height(),
+            });
+        }
+        let mut result = Matrix::identity(self.width()).map(|&n| reduce(n));
+        let mut base = self.map(|&n| reduce(n));
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.matmul_with(&base, reduce).unwrap();
+            }
+            exp >>= 1;
+            // Skip the last squaring, it could overflow for nothing
+            if exp > 0 {
+                base = base.matmul_with(&base, reduce).unwrap();
+            }
+        }
+        Ok(result)
+    }
+
+    fn matmul_with<R>(&self, other: &Matrix<T>, reduce: R) -> Result<Matrix<T>, MatrixError>
+    where
+        R: Fn(T) -> T,
+    {
+        if self.width() != other.height() {
+            return Err(MatrixError::ShapeMismatch {
+                left: (self.width(), self.height()),
+                right: (other.width(), other.height()),
+            });
+        }
+        let mut product = Matrix::new_with(other.width(), self.height(), || T::ZERO);
+        for (y, row) in self.rows().enumerate() {
+            for (k, &a) in row.iter().enumerate() {
+                for (x, &b) in other.row(k).iter().enumerate() {
+                    product[(x, y)] = reduce(product[(x, y)] + reduce(a * b));
+                }
+            }
+        }
+        Ok(product)
+    }
+}
+
+macro_rules! impl_elementwise {
+    ($op:ident, $method:ident) => {
+        impl<T: Num> ops::$op<&Matrix<T>> for &Matrix<T> {
+            type Output = Matrix<T>;
+
+            fn $method(self, rhs: &Matrix<T>) -> Self::Output {
+                self.zip_with(rhs, |&a, &b| ops::$op::$method(a, b))
+            }
+        }
+
+        impl<T: Num> ops::$op for Matrix<T> {
+            type Output = Matrix<T>;
+
+            fn $method(self, rhs: Matrix<T>) -> Self::Output {
+                ops::$op::$method(&self, &rhs)
+            }
+        }
+    };
+}
+
+impl_elementwise!(Add, add);
+impl_elementwise!(Sub, sub);
+impl_elementwise!(Mul, mul);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_elementwise() {
+        let a = Matrix::new([1, 2, 3, 4], 2, 2);
+        let b = Matrix::new([5, 6, 7, 8], 2, 2);
+        assert_eq!((&a + &b).vec, vec![6, 8, 10, 12]);
+        assert_eq!((&b - &a).vec, vec![4, 4, 4, 4]);
+        assert_eq!((a.clone() * b.clone()).vec, vec![5, 12, 21, 32]);
+        assert_eq!(a.map(|n| n * 10).vec, vec![10, 20, 30, 40]);
+        assert_eq!(a.zip_with(&b, |x, y| x.max(y) - x.min(y)).vec, vec![4; 4]);
+        assert!(a
+            .try_zip_with(&Matrix::new([1, 2], 2, 1), |x, y| x + y)
+            .is_err());
+    }
+
+    #[test]
+    fn test_matmul() {
+        // 1 2 3     7  8
+        // 4 5 6  ×  9 10
+        //          11 12
+        let a = Matrix::new(1..=6, 3, 2);
+        let b = Matrix::new(7..=12, 2, 3);
+        assert_eq!(a.matmul(&b), Matrix::new([58, 64, 139, 154], 2, 2));
+        assert!(a.try_matmul(&a).is_err());
+        assert_eq!(b.matmul(&Matrix::identity(2)), b);
+    }
+
+    #[test]
+    fn test_pow() {
+        let fib = Matrix::new([1u64, 1, 1, 0], 2, 2);
+        assert_eq!(fib.pow(0), Matrix::identity(2));
+        assert_eq!(fib.pow(10)[(1, 0)], 55);
+        assert_eq!(fib.pow(90)[(1, 0)], 2880067194370816120);
+        // F(10^18) mod 10^9 + 7
+        assert_eq!(
+            fib.pow_mod(1_000_000_000_000_000_000, 1_000_000_007)[(1, 0)],
+            209783453
+        );
+        assert_eq!(fib.try_pow_mod(2, 0), Err(MatrixError::ZeroModulus));
+    }
+
+    #[test]
+    fn test_try_pow_non_square() {
+        let matrix = Matrix::new(0..6u64, 3, 2);
+        let not_square = MatrixError::NotSquare {
+            width: 3,
+            height: 2,
+        };
+        assert_eq!(matrix.try_pow(2), Err(not_square.clone()));
+        assert_eq!(matrix.try_pow_mod(2, 7), Err(not_square));
+    }
+
+    #[test]
+    #[should_panic(expected = "Matrix with size (3, 2) is not square")]
+    fn test_pow_non_square() {
+        Matrix::new(0..6u64, 3, 2).pow(2);
+    }
+}
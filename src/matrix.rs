@@ -1,5 +1,19 @@
+use crate::pos::{Neighborhood, Pos};
+use std::error::Error;
 use std::fmt;
+use std::iter;
 use std::ops;
+use std::slice;
+
+/// The cells of a single row or column.
+pub type Line<'a, T> = iter::Take<iter::StepBy<iter::Skip<slice::Iter<'a, T>>>>;
+
+/// Rows run along the x axis and are stacked along y, columns the opposite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Axis {
+    Row,
+    Col,
+}
 
 #[derive(Clone, PartialEq, Eq, Hash)]
 pub struct Matrix<T> {
@@ -10,86 +24,184 @@ pub struct Matrix<T> {
 
 impl<T> Matrix<T> {
     pub fn new<I>(items: I, width: usize, height: usize) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        Self::try_new(items, width, height).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_new<I>(items: I, width: usize, height: usize) -> Result<Self, MatrixError>
     where
         I: IntoIterator<Item = T>,
     {
         let vec: Vec<T> = items.into_iter().collect();
-        assert_eq!(vec.len(), width * height);
-        Self { vec, width, height }
+        if vec.len() != width * height {
+            return Err(MatrixError::SizeMismatch {
+                len: vec.len(),
+                width,
+                height,
+            });
+        }
+        Ok(Self { vec, width, height })
     }
 
-    pub fn get(&self, x: isize, y: isize) -> Option<&T> {
-        if x < 0
-            || x > (self.width - 1).try_into().unwrap()
-            || y < 0
-            || y > (self.height - 1).try_into().unwrap()
-        {
-            return None;
+    /// Parses a rectangular block of text, one cell per char.
+    ///
+    /// Lines may end with `\n` or `\r\n`, the final newline is optional and
+    /// trailing blank lines are ignored. Every row must be as wide as the
+    /// first one.
+    pub fn parse_grid<F, E>(input: &str, mut f: F) -> Result<Self, ParseGridError<E>>
+    where
+        F: FnMut(char) -> Result<T, E>,
+    {
+        let mut lines: Vec<&str> = input
+            .split('\n')
+            .map(|l| l.strip_suffix('\r').unwrap_or(l))
+            .collect();
+        while lines.last().is_some_and(|l| l.is_empty()) {
+            lines.pop();
         }
-        let x: usize = x.try_into().unwrap();
-        let y: usize = y.try_into().unwrap();
-        self.vec.get(x + y * self.width)
+        let width = lines.first().map_or(0, |l| l.chars().count());
+        let height = lines.len();
+        let mut vec = Vec::with_capacity(width * height);
+        for (row, line) in lines.into_iter().enumerate() {
+            let mut found = 0;
+            for (col, ch) in line.chars().enumerate() {
+                if col >= width {
+                    found = line.chars().count();
+                    break;
+                }
+                let item = f(ch).map_err(|error| ParseGridError::Cell {
+                    row,
+                    col,
+                    ch,
+                    error,
+                })?;
+                vec.push(item);
+                found += 1;
+            }
+            if found != width {
+                return Err(ParseGridError::Ragged {
+                    row,
+                    expected: width,
+                    found,
+                });
+            }
+        }
+        Ok(Self { vec, width, height })
+    }
+
+    pub fn get(&self, x: isize, y: isize) -> Option<&T> {
+        let x = x.try_into().ok()?;
+        let y = y.try_into().ok()?;
+        self.try_get(x, y).ok()
     }
 
     pub fn get_mut(&mut self, x: isize, y: isize) -> Option<&mut T> {
-        if x < 0
-            || x > (self.width - 1).try_into().unwrap()
-            || y < 0
-            || y > (self.height - 1).try_into().unwrap()
-        {
-            return None;
+        let x = x.try_into().ok()?;
+        let y = y.try_into().ok()?;
+        self.try_get_mut(x, y).ok()
+    }
+
+    pub fn try_get(&self, x: usize, y: usize) -> Result<&T, MatrixError> {
+        let idx = self.checked_index(x, y)?;
+        Ok(&self.vec[idx])
+    }
+
+    pub fn try_get_mut(&mut self, x: usize, y: usize) -> Result<&mut T, MatrixError> {
+        let idx = self.checked_index(x, y)?;
+        Ok(&mut self.vec[idx])
+    }
+
+    fn checked_index(&self, x: usize, y: usize) -> Result<usize, MatrixError> {
+        if x >= self.width || y >= self.height {
+            return Err(MatrixError::OutOfBounds {
+                x,
+                y,
+                width: self.width,
+                height: self.height,
+            });
         }
-        let x: usize = x.try_into().unwrap();
-        let y: usize = y.try_into().unwrap();
-        self.vec.get_mut(x + y * self.width)
+        Ok(x + y * self.width)
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        self.offset_of(pos).is_some()
+    }
+
+    pub fn get_pos(&self, pos: Pos) -> Option<&T> {
+        self.offset_of(pos).map(|i| &self.vec[i])
+    }
+
+    pub fn get_pos_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.offset_of(pos).map(|i| &mut self.vec[i])
+    }
+
+    pub fn rook_neighbor_pos(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.neighbors(pos, Neighborhood::Rook)
+    }
+
+    pub fn neighbor_pos(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.neighbors(pos, Neighborhood::King)
+    }
+
+    /// The neighbours of `pos` that lie inside the matrix.
+    pub fn neighbors<'a>(
+        &'a self,
+        pos: Pos,
+        neighborhood: Neighborhood<'a>,
+    ) -> impl Iterator<Item = Pos> + 'a {
+        neighborhood
+            .offsets()
+            .map(move |offset| pos + offset)
+            .filter(|p| self.contains(*p))
+    }
+
+    /// Like [`Matrix::neighbors`], along with the neighbouring cells.
+    pub fn neighbors_with<'a>(
+        &'a self,
+        pos: Pos,
+        neighborhood: Neighborhood<'a>,
+    ) -> impl Iterator<Item = (Pos, &'a T)> + 'a {
+        neighborhood.offsets().filter_map(move |offset| {
+            let p = pos + offset;
+            self.get_pos(p).map(|t| (p, t))
+        })
+    }
+
+    fn offset_of(&self, pos: Pos) -> Option<usize> {
+        let (x, y) = <(usize, usize)>::try_from(pos).ok()?;
+        self.checked_index(x, y).ok()
     }
 
     pub fn rook_neighbor_indices(
         &self,
         x: usize,
         y: usize,
-    ) -> impl Iterator<Item = (usize, usize)> {
-        let mut res = Vec::new();
-        if y > 0 {
-            res.push((x, y - 1));
-        }
-        if x > 0 {
-            res.push((x - 1, y));
-        }
-        if y < self.height - 1 {
-            res.push((x, y + 1));
-        }
-        if x < self.width - 1 {
-            res.push((x + 1, y));
-        }
-        res.into_iter()
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbor_indices_in(x, y, Neighborhood::Rook)
     }
 
-    pub fn neighbor_indices(&self, x: usize, y: usize) -> Vec<(usize, usize)> {
-        let mut res = Vec::new();
-        let mut xes = vec![x];
-        let mut yes = vec![y];
-        if y > 0 {
-            yes.push(y - 1);
-        }
-        if y < self.height - 1 {
-            yes.push(y + 1)
-        }
-        if x > 0 {
-            xes.push(x - 1);
-        }
-        if x < self.width - 1 {
-            xes.push(x + 1);
-        }
-        for yy in yes {
-            for &xx in &xes {
-                if xx == x && yy == y {
-                    continue;
-                }
-                res.push((xx, yy));
-            }
-        }
-        res
+    pub fn neighbor_indices(
+        &self,
+        x: usize,
+        y: usize,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbor_indices_in(x, y, Neighborhood::King)
+    }
+
+    /// Like [`Matrix::neighbors`] with `usize` coordinates, and nothing at all
+    /// when `(x, y)` is itself outside the matrix.
+    pub fn neighbor_indices_in<'a>(
+        &'a self,
+        x: usize,
+        y: usize,
+        neighborhood: Neighborhood<'a>,
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        let inside = self.checked_index(x, y).is_ok();
+        self.neighbors(Pos::from((x, y)), neighborhood)
+            .filter(move |_| inside)
+            .map(|p| (p.x as usize, p.y as usize))
     }
 
     pub fn width(&self) -> usize {
@@ -108,13 +220,182 @@ impl<T> Matrix<T> {
         self.vec.is_empty()
     }
 
-    pub fn insert_row_at(&mut self, mut other: Matrix<T>, at: usize) {
-        assert!(other.width() == self.width());
+    pub fn insert_row_at(&mut self, other: Matrix<T>, at: usize) {
+        self.try_insert_row_at(other, at)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Inserts the rows of `other` before row `at`, or after the last row if
+    /// `at` is the height.
+    pub fn try_insert_row_at(
+        &mut self,
+        mut other: Matrix<T>,
+        at: usize,
+    ) -> Result<(), MatrixError> {
+        if other.width() != self.width() {
+            return Err(MatrixError::WidthMismatch {
+                expected: self.width(),
+                found: other.width(),
+            });
+        }
+        if at > self.height {
+            return Err(MatrixError::RowOutOfBounds {
+                y: at,
+                height: self.height,
+            });
+        }
         let idx = at * self.width();
         self.height += other.height;
         let mut after = self.vec.split_off(idx);
         self.vec.append(&mut other.vec);
         self.vec.append(&mut after);
+        Ok(())
+    }
+
+    pub fn insert_col_at(&mut self, other: Matrix<T>, at: usize) {
+        self.try_insert_col_at(other, at)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Inserts the columns of `other` before column `at`, or after the last
+    /// column if `at` is the width.
+    pub fn try_insert_col_at(&mut self, other: Matrix<T>, at: usize) -> Result<(), MatrixError> {
+        if other.height() != self.height() {
+            return Err(MatrixError::HeightMismatch {
+                expected: self.height(),
+                found: other.height(),
+            });
+        }
+        if at > self.width {
+            return Err(MatrixError::ColOutOfBounds {
+                x: at,
+                width: self.width,
+            });
+        }
+        let width = self.width + other.width;
+        let mut old = std::mem::take(&mut self.vec).into_iter();
+        let mut new = other.vec.into_iter();
+        let mut vec = Vec::with_capacity(width * self.height);
+        for _ in 0..self.height {
+            vec.extend(old.by_ref().take(at));
+            vec.extend(new.by_ref().take(other.width));
+            vec.extend(old.by_ref().take(self.width - at));
+        }
+        self.vec = vec;
+        self.width = width;
+        Ok(())
+    }
+
+    pub fn remove_row(&mut self, y: usize) -> Vec<T> {
+        self.try_remove_row(y).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Removes row `y` and returns its items.
+    pub fn try_remove_row(&mut self, y: usize) -> Result<Vec<T>, MatrixError> {
+        if y >= self.height {
+            return Err(MatrixError::RowOutOfBounds {
+                y,
+                height: self.height,
+            });
+        }
+        self.height -= 1;
+        Ok(self
+            .vec
+            .drain(y * self.width..(y + 1) * self.width)
+            .collect())
+    }
+
+    pub fn remove_col(&mut self, x: usize) -> Vec<T> {
+        self.try_remove_col(x).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Removes column `x` and returns its items.
+    pub fn try_remove_col(&mut self, x: usize) -> Result<Vec<T>, MatrixError> {
+        if x >= self.width {
+            return Err(MatrixError::ColOutOfBounds {
+                x,
+                width: self.width,
+            });
+        }
+        let mut removed = Vec::with_capacity(self.height);
+        let capacity = self.len() - self.height;
+        let old = std::mem::replace(&mut self.vec, Vec::with_capacity(capacity));
+        for (i, item) in old.into_iter().enumerate() {
+            if i % self.width == x {
+                removed.push(item);
+            } else {
+                self.vec.push(item);
+            }
+        }
+        self.width -= 1;
+        Ok(removed)
+    }
+
+    /// Repeats `factor` times every row or column for which `predicate`
+    /// holds, `factor` 0 removes them. The predicate walks the line, like
+    /// `|mut line| line.all(|c| *c == '.')`.
+    pub fn expand_where<F>(self, axis: Axis, predicate: F, factor: usize) -> Self
+    where
+        T: Clone,
+        F: Fn(Line<'_, T>) -> bool,
+    {
+        let matches = self.lines_matching(axis, predicate);
+        let repeats = |i: usize| if matches[i] { factor } else { 1 };
+        let (mut width, mut height) = (self.width, self.height);
+        let mut vec = Vec::new();
+        match axis {
+            Axis::Row => {
+                for (y, row) in self.rows().enumerate() {
+                    for _ in 0..repeats(y) {
+                        vec.extend_from_slice(row);
+                    }
+                }
+                height = (0..self.height).map(repeats).sum();
+            }
+            Axis::Col => {
+                for row in self.rows() {
+                    for (x, item) in row.iter().enumerate() {
+                        vec.extend(std::iter::repeat_n(item, repeats(x)).cloned());
+                    }
+                }
+                width = (0..self.width).map(repeats).sum();
+            }
+        }
+        Matrix { vec, width, height }
+    }
+
+    /// Where each row or column would start if [`Matrix::expand_where`] was
+    /// applied, without building the expanded matrix. Handy when `factor` is
+    /// too large for the result to fit in memory.
+    pub fn expanded_coords<F>(&self, axis: Axis, predicate: F, factor: u64) -> Vec<u64>
+    where
+        F: Fn(Line<'_, T>) -> bool,
+    {
+        let mut next = 0;
+        self.lines_matching(axis, predicate)
+            .into_iter()
+            .map(|m| {
+                let coord = next;
+                next += if m { factor } else { 1 };
+                coord
+            })
+            .collect()
+    }
+
+    fn lines_matching<F>(&self, axis: Axis, predicate: F) -> Vec<bool>
+    where
+        F: Fn(Line<'_, T>) -> bool,
+    {
+        let (count, len, start_step, step) = match axis {
+            Axis::Row => (self.height, self.width, self.width, 1),
+            Axis::Col => (self.width, self.height, 1, self.width),
+        };
+        (0..count)
+            .map(|i| {
+                let line = self.vec.iter().skip(i * start_step).step_by(step.max(1));
+                predicate(line.take(len))
+            })
+            .collect()
     }
 
     pub fn expand_contour(self, n: usize, with: T) -> Self
@@ -161,10 +442,61 @@ impl<T> Matrix<T> {
         }
     }
 
+    pub fn map<U, F>(&self, f: F) -> Matrix<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Matrix {
+            vec: self.vec.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// Combines the cells of two matrices of the same size pairwise.
+    pub fn zip_with<U, V, F>(&self, other: &Matrix<U>, f: F) -> Matrix<V>
+    where
+        F: FnMut(&T, &U) -> V,
+    {
+        self.try_zip_with(other, f)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_zip_with<U, V, F>(
+        &self,
+        other: &Matrix<U>,
+        mut f: F,
+    ) -> Result<Matrix<V>, MatrixError>
+    where
+        F: FnMut(&T, &U) -> V,
+    {
+        if (self.width, self.height) != (other.width, other.height) {
+            return Err(MatrixError::ShapeMismatch {
+                left: (self.width, self.height),
+                right: (other.width, other.height),
+            });
+        }
+        Ok(Matrix {
+            vec: self
+                .vec
+                .iter()
+                .zip(&other.vec)
+                .map(|(a, b)| f(a, b))
+                .collect(),
+            width: self.width,
+            height: self.height,
+        })
+    }
+
     pub fn swap(&mut self, a: (usize, usize), b: (usize, usize)) {
-        let idx_a = a.1 * self.width() + a.0;
-        let idx_b = b.1 * self.width() + b.0;
-        self.vec.swap(idx_a, idx_b)
+        self.try_swap(a, b).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_swap(&mut self, a: (usize, usize), b: (usize, usize)) -> Result<(), MatrixError> {
+        let idx_a = self.checked_index(a.0, a.1)?;
+        let idx_b = self.checked_index(b.0, b.1)?;
+        self.vec.swap(idx_a, idx_b);
+        Ok(())
     }
 
     pub fn index_of<F>(&self, f: F) -> Option<(usize, usize)>
@@ -183,24 +515,344 @@ impl<T> Matrix<T> {
             .enumerate()
             .map(|(i, t)| ((i % self.width, i / self.width), t))
     }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        self.check_row(y);
+        &self.vec[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        self.check_row(y);
+        &mut self.vec[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn col(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.check_col(x);
+        self.vec.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn col_mut(&mut self, x: usize) -> impl Iterator<Item = &mut T> {
+        self.check_col(x);
+        self.vec.iter_mut().skip(x).step_by(self.width)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [T]> {
+        let width = self.width;
+        let mut rest = self.vec.as_mut_slice();
+        (0..self.height).map(move |_| {
+            let (row, tail) = std::mem::take(&mut rest).split_at_mut(width);
+            rest = tail;
+            row
+        })
+    }
+
+    pub fn cols(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.col(x))
+    }
+
+    pub fn cols_mut(&mut self) -> impl Iterator<Item = impl Iterator<Item = &mut T>> {
+        // Columns are interleaved in memory, so hand out the cells one by one
+        let mut cols: Vec<Vec<&mut T>> = (0..self.width)
+            .map(|_| Vec::with_capacity(self.height))
+            .collect();
+        for row in self.vec.chunks_mut(self.width.max(1)) {
+            for (col, item) in cols.iter_mut().zip(row) {
+                col.push(item);
+            }
+        }
+        cols.into_iter().map(|col| col.into_iter())
+    }
+
+    /// Swaps rows and columns, `(x, y)` ends up at `(y, x)`.
+    pub fn transpose(self) -> Self {
+        let (width, height) = (self.width, self.height);
+        self.remap(height, width, |x, y| y + x * width)
+    }
+
+    /// Rotates a quarter turn clockwise, the first row becomes the last column.
+    pub fn rotate_cw(self) -> Self {
+        let (width, height) = (self.width, self.height);
+        self.remap(height, width, |x, y| y + (height - 1 - x) * width)
+    }
+
+    /// Rotates a quarter turn counter-clockwise, the first row becomes the
+    /// first column read bottom-up.
+    pub fn rotate_ccw(self) -> Self {
+        let (width, height) = (self.width, self.height);
+        self.remap(height, width, |x, y| (width - 1 - y) + x * width)
+    }
+
+    /// Mirrors left to right.
+    pub fn flip_horizontal(mut self) -> Self {
+        self.flip_horizontal_mut();
+        self
+    }
+
+    /// Mirrors top to bottom.
+    pub fn flip_vertical(mut self) -> Self {
+        self.flip_vertical_mut();
+        self
+    }
+
+    /// In-place [`Matrix::transpose`], only possible on a square matrix.
+    pub fn transpose_mut(&mut self) {
+        self.check_square();
+        for y in 0..self.height {
+            for x in (y + 1)..self.width {
+                self.swap((x, y), (y, x));
+            }
+        }
+    }
+
+    /// In-place [`Matrix::rotate_cw`], only possible on a square matrix.
+    pub fn rotate_cw_mut(&mut self) {
+        self.transpose_mut();
+        self.flip_horizontal_mut();
+    }
+
+    /// In-place [`Matrix::rotate_ccw`], only possible on a square matrix.
+    pub fn rotate_ccw_mut(&mut self) {
+        self.transpose_mut();
+        self.flip_vertical_mut();
+    }
+
+    pub fn flip_horizontal_mut(&mut self) {
+        for row in self.rows_mut() {
+            row.reverse();
+        }
+    }
+
+    pub fn flip_vertical_mut(&mut self) {
+        for y in 0..self.height / 2 {
+            let (top, bottom) = self.vec.split_at_mut((self.height - 1 - y) * self.width);
+            top[y * self.width..(y + 1) * self.width].swap_with_slice(&mut bottom[..self.width]);
+        }
+    }
+
+    /// Builds a `width` by `height` matrix whose cell `(x, y)` is moved from
+    /// index `src(x, y)` of the current one.
+    fn remap<F>(self, width: usize, height: usize, src: F) -> Self
+    where
+        F: Fn(usize, usize) -> usize,
+    {
+        let mut items: Vec<Option<T>> = self.vec.into_iter().map(Some).collect();
+        let vec = (0..width * height)
+            .map(|i| items[src(i % width, i / width)].take().unwrap())
+            .collect();
+        Matrix { vec, width, height }
+    }
+
+    fn check_square(&self) {
+        if self.width != self.height {
+            panic!(
+                "{}",
+                MatrixError::NotSquare {
+                    width: self.width,
+                    height: self.height
+                }
+            );
+        }
+    }
+
+    fn check_row(&self, y: usize) {
+        if y >= self.height {
+            panic!(
+                "{}",
+                MatrixError::RowOutOfBounds {
+                    y,
+                    height: self.height
+                }
+            );
+        }
+    }
+
+    fn check_col(&self, x: usize) {
+        if x >= self.width {
+            panic!(
+                "{}",
+                MatrixError::ColOutOfBounds {
+                    x,
+                    width: self.width
+                }
+            );
+        }
+    }
+}
+
+/// Error returned by the fallible `try_` methods of [`Matrix`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatrixError {
+    /// `len` items cannot fill a `width` by `height` matrix.
+    SizeMismatch {
+        len: usize,
+        width: usize,
+        height: usize,
+    },
+    OutOfBounds {
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    },
+    RowOutOfBounds {
+        y: usize,
+        height: usize,
+    },
+    ColOutOfBounds {
+        x: usize,
+        width: usize,
+    },
+    /// The `w` by `h` rectangle at `(x, y)` does not fit in the matrix.
+    RectOutOfBounds {
+        x: usize,
+        y: usize,
+        w: usize,
+        h: usize,
+        width: usize,
+        height: usize,
+    },
+    /// A matrix with `found` columns was given where `expected` were needed.
+    WidthMismatch {
+        expected: usize,
+        found: usize,
+    },
+    /// A matrix with `found` rows was given where `expected` were needed.
+    HeightMismatch {
+        expected: usize,
+        found: usize,
+    },
+    /// The sizes of two matrices do not fit the operation between them.
+    ShapeMismatch {
+        left: (usize, usize),
+        right: (usize, usize),
+    },
+    /// The operation needs as many rows as columns.
+    NotSquare {
+        width: usize,
+        height: usize,
+    },
+    /// The operation needs at least one cell.
+    Empty {
+        width: usize,
+        height: usize,
+    },
+    /// Arithmetic modulo 0 was asked for.
+    ZeroModulus,
+}
+
+impl fmt::Display for MatrixError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MatrixError::SizeMismatch { len, width, height } => write!(
+                f,
+                "{len} items cannot fill a Matrix with size ({width}, {height})"
+            ),
+            MatrixError::OutOfBounds {
+                x,
+                y,
+                width,
+                height,
+            } => write!(
+                f,
+                "Index ({x}, {y}) out of range for Matrix with size ({width}, {height})"
+            ),
+            MatrixError::RowOutOfBounds { y, height } => {
+                write!(f, "Row {y} out of range for Matrix with height {height}")
+            }
+            MatrixError::ColOutOfBounds { x, width } => {
+                write!(f, "Column {x} out of range for Matrix with width {width}")
+            }
+            MatrixError::RectOutOfBounds {
+                x,
+                y,
+                w,
+                h,
+                width,
+                height,
+            } => write!(
+                f,
+                "Rectangle of size ({w}, {h}) at ({x}, {y}) out of range for Matrix with size ({width}, {height})"
+            ),
+            MatrixError::WidthMismatch { expected, found } => write!(
+                f,
+                "Matrix with width {found} given where width {expected} was expected"
+            ),
+            MatrixError::HeightMismatch { expected, found } => write!(
+                f,
+                "Matrix with height {found} given where height {expected} was expected"
+            ),
+            MatrixError::ShapeMismatch { left, right } => write!(
+                f,
+                "Matrices with sizes {left:?} and {right:?} do not fit together"
+            ),
+            MatrixError::NotSquare { width, height } => {
+                write!(f, "Matrix with size ({width}, {height}) is not square")
+            }
+            MatrixError::Empty { width, height } => {
+                write!(f, "Matrix with size ({width}, {height}) has no cells")
+            }
+            MatrixError::ZeroModulus => write!(f, "Modulus must be non-zero"),
+        }
+    }
+}
+
+impl Error for MatrixError {}
+
+/// Error returned by [`Matrix::parse_grid`]. Rows and columns are 0-based.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseGridError<E> {
+    /// Row `row` has `found` chars where `expected` were needed.
+    Ragged {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// The cell parser rejected `ch`.
+    Cell {
+        row: usize,
+        col: usize,
+        ch: char,
+        error: E,
+    },
+}
+
+impl<E> fmt::Display for ParseGridError<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseGridError::Ragged {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row {row} has {found} columns, expected {expected} like the first row"
+            ),
+            ParseGridError::Cell {
+                row,
+                col,
+                ch,
+                error,
+            } => write!(f, "invalid cell {ch:?} at row {row}, column {col}: {error}"),
+        }
+    }
 }
 
+impl<E> Error for ParseGridError<E> where E: fmt::Debug + fmt::Display {}
+
 impl<T> fmt::Debug for Matrix<T>
 where
     T: fmt::Debug,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut count = 0;
         writeln!(f)?;
-        for item in &self.vec {
-            write!(f, "{item:?}")?;
-            count += 1;
-            if count == self.width {
-                count = 0;
-                writeln!(f)?;
-            }
-        }
-        Ok(())
+        write!(f, "{}", self.render(|item| format!("{item:?}")))
     }
 }
 
@@ -208,25 +860,13 @@ impl<T> ops::Index<(usize, usize)> for Matrix<T> {
     type Output = T;
 
     fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
-        if x > self.width - 1 || y > self.height - 1 {
-            panic!(
-                "Index ({}, {}) out of range for Matrix with size ({}, {})",
-                y, x, self.height, self.width
-            );
-        }
-        &self.vec[x + y * self.width]
+        self.try_get(x, y).unwrap_or_else(|e| panic!("{e}"))
     }
 }
 
 impl<T> ops::IndexMut<(usize, usize)> for Matrix<T> {
     fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut Self::Output {
-        if x > self.width - 1 || y > self.height - 1 {
-            panic!(
-                "Index ({}, {}) out of range for Matrix with size ({}, {})",
-                y, x, self.height, self.width
-            );
-        }
-        &mut self.vec[x + y * self.width]
+        self.try_get_mut(x, y).unwrap_or_else(|e| panic!("{e}"))
     }
 }
 
@@ -234,25 +874,38 @@ impl<T> ops::Index<(&usize, &usize)> for Matrix<T> {
     type Output = T;
 
     fn index(&self, (x, y): (&usize, &usize)) -> &Self::Output {
-        if *x > self.width - 1 || *y > self.height - 1 {
-            panic!(
-                "Index ({}, {}) out of range for Matrix with size ({}, {})",
-                y, x, self.height, self.width
-            );
-        }
-        &self.vec[x + y * self.width]
+        &self[(*x, *y)]
     }
 }
 
 impl<T> ops::IndexMut<(&usize, &usize)> for Matrix<T> {
     fn index_mut(&mut self, (x, y): (&usize, &usize)) -> &mut Self::Output {
-        if *x > self.width - 1 || *y > self.height - 1 {
+        &mut self[(*x, *y)]
+    }
+}
+
+impl<T> ops::Index<Pos> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &Self::Output {
+        self.get_pos(pos).unwrap_or_else(|| {
             panic!(
-                "Index ({}, {}) out of range for Matrix with size ({}, {})",
-                y, x, self.height, self.width
-            );
-        }
-        &mut self.vec[x + y * self.width]
+                "Index {:?} out of range for Matrix with size ({}, {})",
+                pos, self.width, self.height
+            )
+        })
+    }
+}
+
+impl<T> ops::IndexMut<Pos> for Matrix<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut Self::Output {
+        let (width, height) = (self.width, self.height);
+        self.get_pos_mut(pos).unwrap_or_else(|| {
+            panic!(
+                "Index {:?} out of range for Matrix with size ({}, {})",
+                pos, width, height
+            )
+        })
     }
 }
 
@@ -267,4 +920,281 @@ mod test {
         assert_eq!(matrix[(0, 0)], 3);
         assert_eq!(matrix[(0, 1)], 0);
     }
+
+    #[test]
+    fn test_try_methods() {
+        assert_eq!(
+            Matrix::try_new(0..5, 3, 2),
+            Err(MatrixError::SizeMismatch {
+                len: 5,
+                width: 3,
+                height: 2
+            })
+        );
+        let mut matrix = Matrix::try_new(0..6, 3, 2).unwrap();
+        assert_eq!(matrix.try_get(2, 1), Ok(&5));
+        assert_eq!(
+            matrix.try_get(1, 2),
+            Err(MatrixError::OutOfBounds {
+                x: 1,
+                y: 2,
+                width: 3,
+                height: 2
+            })
+        );
+        assert!(matrix.try_swap((0, 0), (3, 0)).is_err());
+        assert_eq!(matrix.vec, vec![0, 1, 2, 3, 4, 5]);
+
+        let row = Matrix::new([6, 7], 2, 1);
+        assert_eq!(
+            matrix.try_insert_row_at(row, 1),
+            Err(MatrixError::WidthMismatch {
+                expected: 3,
+                found: 2
+            })
+        );
+        let row = Matrix::new([6, 7, 8], 3, 1);
+        assert!(matrix.try_insert_row_at(row.clone(), 3).is_err());
+        matrix.try_insert_row_at(row, 2).unwrap();
+        assert_eq!(matrix.row(2), &[6, 7, 8]);
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut matrix = Matrix::new(0..6, 3, 2);
+        matrix.insert_col_at(Matrix::new([10, 11, 12, 13], 2, 2), 1);
+        assert_eq!(matrix.width(), 5);
+        assert_eq!(matrix.row(1), &[3, 12, 13, 4, 5]);
+        assert!(matrix.try_insert_col_at(Matrix::new([0], 1, 1), 0).is_err());
+        assert!(matrix
+            .try_insert_col_at(Matrix::new([0, 0], 1, 2), 6)
+            .is_err());
+
+        assert_eq!(matrix.remove_col(2), vec![11, 13]);
+        assert_eq!(
+            matrix.try_remove_col(4),
+            Err(MatrixError::ColOutOfBounds { x: 4, width: 4 })
+        );
+        assert_eq!(matrix.remove_row(0), vec![0, 10, 1, 2]);
+        assert_eq!(
+            matrix.try_remove_row(1),
+            Err(MatrixError::RowOutOfBounds { y: 1, height: 1 })
+        );
+        assert_eq!((matrix.width(), matrix.height()), (4, 1));
+        assert_eq!(matrix.vec, vec![3, 12, 4, 5]);
+    }
+
+    const GALAXIES: &str = "\
+...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....
+";
+
+    #[test]
+    fn test_expand_where() {
+        let matrix = Matrix::parse_grid(GALAXIES, Ok::<_, ()>).unwrap();
+        let empty = |mut line: Line<char>| line.all(|c| *c == '.');
+        let expanded = matrix
+            .clone()
+            .expand_where(Axis::Row, empty, 2)
+            .expand_where(Axis::Col, empty, 2);
+        assert_eq!((expanded.width(), expanded.height()), (13, 12));
+        assert_eq!(expanded.row(0).iter().collect::<String>(), "....#........");
+
+        let galaxies: Vec<_> = expanded
+            .iter_pos()
+            .filter(|(_, c)| **c == '#')
+            .map(|(pos, _)| Pos::from(pos))
+            .collect();
+        let sum_distances = |galaxies: &[Pos]| -> u64 {
+            let mut sum = 0;
+            for (i, a) in galaxies.iter().enumerate() {
+                for b in &galaxies[i + 1..] {
+                    sum += a.manhattan(*b);
+                }
+            }
+            sum
+        };
+        assert_eq!(sum_distances(&galaxies), 374);
+
+        for (factor, expected) in [(2, 374), (10, 1030), (100, 8410)] {
+            let xs = matrix.expanded_coords(Axis::Col, empty, factor);
+            let ys = matrix.expanded_coords(Axis::Row, empty, factor);
+            let galaxies: Vec<_> = matrix
+                .iter_pos()
+                .filter(|(_, c)| **c == '#')
+                .map(|((x, y), _)| Pos::new(xs[x] as i64, ys[y] as i64))
+                .collect();
+            assert_eq!(sum_distances(&galaxies), expected);
+        }
+    }
+
+    #[test]
+    fn test_empty() {
+        for (width, height) in [(0, 0), (0, 3), (3, 0)] {
+            let mut matrix: Matrix<u8> = Matrix::new([], width, height);
+            assert!(matrix.is_empty());
+            assert_eq!(matrix.get(0, 0), None);
+            assert_eq!(matrix.get_mut(0, 0), None);
+            assert_eq!(matrix.get_pos(Pos::ORIGIN), None);
+            assert!(matrix.try_get(0, 0).is_err());
+            assert_eq!(matrix.rook_neighbor_indices(0, 0).count(), 0);
+            assert_eq!(matrix.neighbor_indices(0, 0).count(), 0);
+            assert_eq!(matrix.index_of(|_| true), None);
+            assert_eq!(matrix.iter_pos().count(), 0);
+            assert_eq!(matrix.rows().count(), height);
+            assert_eq!(matrix.rows_mut().count(), height);
+            assert_eq!(matrix.cols().count(), width);
+            assert_eq!(matrix.cols_mut().count(), width);
+            matrix.flip_horizontal_mut();
+            matrix.flip_vertical_mut();
+            let matrix = matrix.rotate_cw().transpose();
+            assert_eq!((matrix.width(), matrix.height()), (width, height));
+            let matrix = matrix.expand_contour(1, 0);
+            assert_eq!(matrix.len(), (width + 2) * (height + 2));
+            let _ = format!("{matrix:?}");
+        }
+    }
+
+    #[test]
+    fn test_rows_cols() {
+        let mut matrix = Matrix::new(0..6, 3, 2);
+        assert_eq!(matrix.row(1), &[3, 4, 5]);
+        assert_eq!(matrix.col(1).copied().collect::<Vec<_>>(), vec![1, 4]);
+        assert_eq!(matrix.rows().count(), 2);
+        let cols: Vec<Vec<_>> = matrix.cols().map(|c| c.copied().collect()).collect();
+        assert_eq!(cols, vec![vec![0, 3], vec![1, 4], vec![2, 5]]);
+
+        matrix.row_mut(0)[2] = 10;
+        for c in matrix.col_mut(0) {
+            *c += 100;
+        }
+        for (x, col) in matrix.cols_mut().enumerate() {
+            for c in col {
+                *c += x * 1000;
+            }
+        }
+        assert_eq!(matrix.vec, vec![100, 1001, 2010, 103, 1004, 2005]);
+        for row in matrix.rows_mut() {
+            row.reverse();
+        }
+        assert_eq!(matrix.row(0), &[2010, 1001, 100]);
+    }
+
+    #[test]
+    fn test_transforms() {
+        // 0 1 2
+        // 3 4 5
+        let matrix = Matrix::new(0..6, 3, 2);
+
+        let t = matrix.clone().transpose();
+        assert_eq!((t.width(), t.height()), (2, 3));
+        assert_eq!(t.vec, vec![0, 3, 1, 4, 2, 5]);
+
+        let cw = matrix.clone().rotate_cw();
+        assert_eq!((cw.width(), cw.height()), (2, 3));
+        assert_eq!(cw.vec, vec![3, 0, 4, 1, 5, 2]);
+
+        let ccw = matrix.clone().rotate_ccw();
+        assert_eq!(ccw.vec, vec![2, 5, 1, 4, 0, 3]);
+        assert_eq!(ccw.rotate_cw(), matrix);
+
+        assert_eq!(matrix.clone().flip_horizontal().vec, vec![2, 1, 0, 5, 4, 3]);
+        assert_eq!(matrix.clone().flip_vertical().vec, vec![3, 4, 5, 0, 1, 2]);
+    }
+
+    #[test]
+    fn test_transforms_in_place() {
+        let square = Matrix::new(0..9, 3, 3);
+        for (owned, in_place) in [
+            (
+                Matrix::transpose as fn(Matrix<i32>) -> Matrix<i32>,
+                Matrix::transpose_mut as fn(&mut Matrix<i32>),
+            ),
+            (Matrix::rotate_cw, Matrix::rotate_cw_mut),
+            (Matrix::rotate_ccw, Matrix::rotate_ccw_mut),
+            (Matrix::flip_horizontal, Matrix::flip_horizontal_mut),
+            (Matrix::flip_vertical, Matrix::flip_vertical_mut),
+        ] {
+            let mut m = square.clone();
+            in_place(&mut m);
+            assert_eq!(m, owned(square.clone()));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Matrix with size (3, 2) is not square")]
+    fn test_transpose_mut_non_square() {
+        Matrix::new(0..6, 3, 2).transpose_mut();
+    }
+
+    #[test]
+    fn test_pos_access() {
+        let mut matrix = Matrix::new(0..6, 3, 2);
+        assert_eq!(matrix[Pos::new(2, 1)], 5);
+        assert_eq!(matrix.get_pos(Pos::new(-1, 0)), None);
+        assert_eq!(matrix.get_pos(Pos::new(3, 0)), None);
+        matrix[Pos::new(0, 1)] = 30;
+        assert_eq!(matrix[(0, 1)], 30);
+
+        let rook: Vec<_> = matrix.rook_neighbor_pos(Pos::new(0, 0)).collect();
+        assert_eq!(rook, vec![Pos::new(1, 0), Pos::new(0, 1)]);
+        assert_eq!(matrix.neighbor_pos(Pos::new(1, 0)).count(), 5);
+        assert_eq!(
+            matrix
+                .neighbors(Pos::new(0, 0), Neighborhood::Moore(2))
+                .count(),
+            5
+        );
+        let sum: i32 = matrix
+            .neighbors_with(Pos::new(1, 1), Neighborhood::VonNeumann(1))
+            .map(|(_, t)| t)
+            .sum();
+        assert_eq!(sum, 1 + 30 + 5);
+        assert_eq!(matrix.neighbor_indices(1, 0).count(), 5);
+        assert_eq!(matrix.rook_neighbor_indices(3, 0).count(), 0);
+    }
+
+    #[test]
+    fn test_parse_grid() {
+        let parse = |input| Matrix::parse_grid(input, |c| c.to_digit(10).ok_or("not a digit"));
+
+        let matrix = parse("123\r\n456\r\n\n\n").unwrap();
+        assert_eq!((matrix.width(), matrix.height()), (3, 2));
+        assert_eq!(matrix.vec, vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(parse("123\n456").unwrap(), matrix);
+
+        assert_eq!(
+            parse("123\n45\n"),
+            Err(ParseGridError::Ragged {
+                row: 1,
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            parse("123\n4567\n"),
+            Err(ParseGridError::Ragged {
+                row: 1,
+                expected: 3,
+                found: 4
+            })
+        );
+        assert_eq!(
+            parse("123\n4x6\n"),
+            Err(ParseGridError::Cell {
+                row: 1,
+                col: 1,
+                ch: 'x',
+                error: "not a digit"
+            })
+        );
+    }
 }
@@ -0,0 +1,169 @@
+use crate::matrix::Matrix;
+
+/// One of the 8 ways to turn and mirror a grid: mirror left to right first
+/// if `flipped`, then rotate `quarter_turns` times clockwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Transform {
+    pub flipped: bool,
+    pub quarter_turns: u8,
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform {
+        flipped: false,
+        quarter_turns: 0,
+    };
+
+    /// All the rotations, then all the rotations of the mirrored grid.
+    pub const ALL: [Transform; 8] = {
+        let mut all = [Transform::IDENTITY; 8];
+        let mut i = 0;
+        while i < 8 {
+            all[i] = Transform {
+                flipped: i >= 4,
+                quarter_turns: (i % 4) as u8,
+            };
+            i += 1;
+        }
+        all
+    };
+
+    pub fn apply<T>(self, matrix: Matrix<T>) -> Matrix<T> {
+        let mut matrix = if self.flipped {
+            matrix.flip_horizontal()
+        } else {
+            matrix
+        };
+        for _ in 0..self.quarter_turns % 4 {
+            matrix = matrix.rotate_cw();
+        }
+        matrix
+    }
+}
+
+/// A place where a pattern was found by [`Matrix::find_pattern_transformed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PatternMatch {
+    /// Top left corner of the match.
+    pub pos: (usize, usize),
+    /// How the pattern was turned to match.
+    pub transform: Transform,
+}
+
+impl<T> Matrix<T> {
+    /// Top left corners of every place where `matcher(pattern_cell, cell)`
+    /// holds for the whole of `pattern`, row by row. Wildcards are pattern
+    /// cells for which `matcher` always holds. An empty pattern matches
+    /// nowhere.
+    pub fn find_pattern<P, F>(&self, pattern: &Matrix<P>, matcher: F) -> Vec<(usize, usize)>
+    where
+        F: Fn(&P, &T) -> bool,
+    {
+        let fits = pattern.width() <= self.width() && pattern.height() <= self.height();
+        if pattern.is_empty() || !fits {
+            return Vec::new();
+        }
+        let mut found = Vec::new();
+        for y in 0..=self.height() - pattern.height() {
+            for x in 0..=self.width() - pattern.width() {
+                if pattern
+                    .iter_pos()
+                    .all(|((px, py), p)| matcher(p, &self[(x + px, y + py)]))
+                {
+                    found.push((x, y));
+                }
+            }
+        }
+        found
+    }
+
+    /// Like [`Matrix::find_pattern`], trying each of [`Transform::ALL`] on
+    /// `pattern`. Transforms giving back a pattern already tried, as happens
+    /// with symmetric ones, are skipped.
+    pub fn find_pattern_transformed<P, F>(
+        &self,
+        pattern: &Matrix<P>,
+        matcher: F,
+    ) -> Vec<PatternMatch>
+    where
+        P: Clone + PartialEq,
+        F: Fn(&P, &T) -> bool,
+    {
+        let mut tried: Vec<Matrix<P>> = Vec::new();
+        let mut found = Vec::new();
+        for transform in Transform::ALL {
+            let turned = transform.apply(pattern.clone());
+            if tried.contains(&turned) {
+                continue;
+            }
+            found.extend(
+                self.find_pattern(&turned, &matcher)
+                    .into_iter()
+                    .map(|pos| PatternMatch { pos, transform }),
+            );
+            tried.push(turned);
+        }
+        found
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const GRID: &str = "\
+#..#....
+###..#..
+.#..###.
+.....#..
+";
+
+    fn parse(s: &str) -> Matrix<char> {
+        Matrix::parse_grid(s, Ok::<_, ()>).unwrap()
+    }
+
+    #[test]
+    fn test_find_pattern() {
+        let grid = parse(GRID);
+        // `?` matches anything
+        let pattern = parse("#??\n###\n");
+        let matcher = |p: &char, c: &char| *p == '?' || p == c;
+        assert_eq!(grid.find_pattern(&pattern, matcher), vec![(0, 0)]);
+        assert_eq!(grid.find_pattern(&parse("#"), matcher).len(), 11);
+        assert!(grid.find_pattern(&parse("#########"), matcher).is_empty());
+        for (width, height) in [(0, 0), (0, 2), (3, 0)] {
+            let empty = Matrix::new_with(width, height, || '?');
+            assert!(grid.find_pattern(&empty, matcher).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_find_pattern_transformed() {
+        let grid = parse(GRID);
+        let matcher = |p: &char, c: &char| *p == '.' || p == c;
+
+        // A plus sign is the same whichever way it is turned
+        let plus = parse(".#.\n###\n.#.\n");
+        let found = grid.find_pattern_transformed(&plus, matcher);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].pos, (4, 1));
+        assert_eq!(found[0].transform, Transform::IDENTITY);
+
+        // The T is there both ways up
+        let tee = parse("###\n.#.\n");
+        let found = grid.find_pattern_transformed(&tee, matcher);
+        let up = Transform {
+            flipped: false,
+            quarter_turns: 2,
+        };
+        assert!(found.contains(&PatternMatch {
+            pos: (0, 1),
+            transform: Transform::IDENTITY
+        }));
+        assert!(found.contains(&PatternMatch {
+            pos: (4, 1),
+            transform: up
+        }));
+        assert_eq!(up.apply(tee), parse(".#.\n###\n"));
+    }
+}
@@ -0,0 +1,338 @@
+use std::fmt;
+use std::ops;
+
+/// A point on a grid, `x` grows to the right and `y` grows downwards.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Pos {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Pos {
+    pub const ORIGIN: Pos = Pos { x: 0, y: 0 };
+
+    pub const fn new(x: i64, y: i64) -> Self {
+        Pos { x, y }
+    }
+
+    pub fn manhattan(self, other: Pos) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    pub fn chebyshev(self, other: Pos) -> u64 {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    pub fn step(self, direction: Direction) -> Self {
+        self + direction.delta()
+    }
+}
+
+impl fmt::Debug for Pos {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl From<(usize, usize)> for Pos {
+    fn from((x, y): (usize, usize)) -> Self {
+        Pos::new(x as i64, y as i64)
+    }
+}
+
+impl From<(i64, i64)> for Pos {
+    fn from((x, y): (i64, i64)) -> Self {
+        Pos::new(x, y)
+    }
+}
+
+impl From<Direction> for Pos {
+    fn from(direction: Direction) -> Self {
+        direction.delta()
+    }
+}
+
+impl TryFrom<Pos> for (usize, usize) {
+    type Error = std::num::TryFromIntError;
+
+    fn try_from(pos: Pos) -> Result<Self, Self::Error> {
+        Ok((pos.x.try_into()?, pos.y.try_into()?))
+    }
+}
+
+impl ops::Add for Pos {
+    type Output = Pos;
+
+    fn add(self, rhs: Pos) -> Self::Output {
+        Pos::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl ops::Sub for Pos {
+    type Output = Pos;
+
+    fn sub(self, rhs: Pos) -> Self::Output {
+        Pos::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl ops::AddAssign for Pos {
+    fn add_assign(&mut self, rhs: Pos) {
+        *self = *self + rhs;
+    }
+}
+
+impl ops::SubAssign for Pos {
+    fn sub_assign(&mut self, rhs: Pos) {
+        *self = *self - rhs;
+    }
+}
+
+impl ops::Neg for Pos {
+    type Output = Pos;
+
+    fn neg(self) -> Self::Output {
+        Pos::new(-self.x, -self.y)
+    }
+}
+
+impl ops::Mul<i64> for Pos {
+    type Output = Pos;
+
+    fn mul(self, rhs: i64) -> Self::Output {
+        Pos::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+/// Compass directions in screen coordinates: north is up, so it decreases `y`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    /// The 4 rook directions, clockwise from north.
+    pub const CARDINAL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    /// All 8 king directions, clockwise from north.
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    pub fn delta(self) -> Pos {
+        let (x, y) = match self {
+            Direction::North => (0, -1),
+            Direction::NorthEast => (1, -1),
+            Direction::East => (1, 0),
+            Direction::SouthEast => (1, 1),
+            Direction::South => (0, 1),
+            Direction::SouthWest => (-1, 1),
+            Direction::West => (-1, 0),
+            Direction::NorthWest => (-1, -1),
+        };
+        Pos::new(x, y)
+    }
+
+    /// The direction pointing the same way as `delta`, whatever its length.
+    pub fn from_delta(delta: Pos) -> Option<Self> {
+        if delta.x != 0 && delta.y != 0 && delta.x.abs() != delta.y.abs() {
+            return None;
+        }
+        let unit = Pos::new(delta.x.signum(), delta.y.signum());
+        Direction::ALL.into_iter().find(|d| d.delta() == unit)
+    }
+
+    pub fn is_cardinal(self) -> bool {
+        matches!(
+            self,
+            Direction::North | Direction::East | Direction::South | Direction::West
+        )
+    }
+
+    pub fn opposite(self) -> Self {
+        self.rotate(4)
+    }
+
+    /// Quarter turn counter-clockwise.
+    pub fn turn_left(self) -> Self {
+        self.rotate(6)
+    }
+
+    /// Quarter turn clockwise.
+    pub fn turn_right(self) -> Self {
+        self.rotate(2)
+    }
+
+    /// Eighth of a turn counter-clockwise.
+    pub fn turn_left_45(self) -> Self {
+        self.rotate(7)
+    }
+
+    /// Eighth of a turn clockwise.
+    pub fn turn_right_45(self) -> Self {
+        self.rotate(1)
+    }
+
+    fn rotate(self, eighths: usize) -> Self {
+        Direction::ALL[(self as usize + eighths) % 8]
+    }
+}
+
+/// Which cells count as adjacent to a given one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Neighborhood<'a> {
+    /// The 4 cells sharing an edge.
+    Rook,
+    /// The 8 cells sharing an edge or a corner.
+    King,
+    /// The cells at a Manhattan distance of at most `r`.
+    VonNeumann(u32),
+    /// The cells at a Chebyshev distance of at most `r`.
+    Moore(u32),
+    /// The cells at the given offsets.
+    Custom(&'a [Pos]),
+}
+
+impl<'a> Neighborhood<'a> {
+    /// The offsets from a cell to its neighbours, never `(0, 0)` except in a
+    /// custom stencil that asks for it.
+    pub fn offsets(self) -> Offsets<'a> {
+        let r = match self {
+            Neighborhood::VonNeumann(r) | Neighborhood::Moore(r) => r as i64,
+            _ => 0,
+        };
+        Offsets {
+            neighborhood: self,
+            i: 0,
+            cursor: Pos::new(-r, -r),
+        }
+    }
+}
+
+/// Iterator over the offsets of a [`Neighborhood`], without allocating.
+#[derive(Debug, Clone)]
+pub struct Offsets<'a> {
+    neighborhood: Neighborhood<'a>,
+    i: usize,
+    cursor: Pos,
+}
+
+impl Iterator for Offsets<'_> {
+    type Item = Pos;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (r, within): (i64, fn(Pos, i64) -> bool) = match self.neighborhood {
+            Neighborhood::Rook => {
+                let d = Direction::CARDINAL.get(self.i)?;
+                self.i += 1;
+                return Some(d.delta());
+            }
+            Neighborhood::King => {
+                let d = Direction::ALL.get(self.i)?;
+                self.i += 1;
+                return Some(d.delta());
+            }
+            Neighborhood::Custom(offsets) => {
+                let offset = offsets.get(self.i)?;
+                self.i += 1;
+                return Some(*offset);
+            }
+            Neighborhood::VonNeumann(r) => (r as i64, |p, r| p.manhattan(Pos::ORIGIN) <= r as u64),
+            Neighborhood::Moore(r) => (r as i64, |_, _| true),
+        };
+        // Scan the (2r + 1) square row by row, keeping the cells within reach
+        while self.cursor.y <= r {
+            let offset = self.cursor;
+            self.cursor.x += 1;
+            if self.cursor.x > r {
+                self.cursor = Pos::new(-r, self.cursor.y + 1);
+            }
+            if offset != Pos::ORIGIN && within(offset, r) {
+                return Some(offset);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pos_ops() {
+        let a = Pos::new(1, -2);
+        let b = Pos::new(-3, 4);
+        assert_eq!(a + b, Pos::new(-2, 2));
+        assert_eq!(a - b, Pos::new(4, -6));
+        assert_eq!(a.manhattan(b), 10);
+        assert_eq!(a.chebyshev(b), 6);
+        assert_eq!(a.step(Direction::North), Pos::new(1, -3));
+        assert_eq!(<(usize, usize)>::try_from(a).ok(), None);
+        assert_eq!(<(usize, usize)>::try_from(-b).ok(), None);
+        assert_eq!(
+            <(usize, usize)>::try_from(Pos::new(3, 4)).ok(),
+            Some((3, 4))
+        );
+    }
+
+    #[test]
+    fn test_direction() {
+        use Direction as D;
+        assert_eq!(D::North.delta(), Pos::new(0, -1));
+        assert_eq!(D::North.turn_right(), D::East);
+        assert_eq!(D::North.turn_left(), D::West);
+        assert_eq!(D::West.turn_right_45(), D::NorthWest);
+        assert_eq!(D::SouthEast.opposite(), D::NorthWest);
+        assert!(D::CARDINAL.iter().all(|d| d.is_cardinal()));
+        for d in D::ALL {
+            assert_eq!(D::from_delta(d.delta() * 3), Some(d));
+            assert_eq!(d.turn_left().turn_right(), d);
+        }
+        assert_eq!(D::from_delta(Pos::new(2, 1)), None);
+        assert_eq!(D::from_delta(Pos::ORIGIN), None);
+    }
+
+    #[test]
+    fn test_neighborhood_offsets() {
+        let rook: Vec<_> = Neighborhood::Rook.offsets().collect();
+        let mut von_neumann: Vec<_> = Neighborhood::VonNeumann(1).offsets().collect();
+        von_neumann.sort();
+        let mut sorted_rook = rook.clone();
+        sorted_rook.sort();
+        assert_eq!(von_neumann, sorted_rook);
+        assert_eq!(rook[0], Direction::North.delta());
+
+        assert_eq!(Neighborhood::King.offsets().count(), 8);
+        assert_eq!(Neighborhood::Moore(1).offsets().count(), 8);
+        assert_eq!(Neighborhood::Moore(2).offsets().count(), 24);
+        assert_eq!(Neighborhood::VonNeumann(2).offsets().count(), 12);
+        assert_eq!(Neighborhood::VonNeumann(0).offsets().count(), 0);
+        assert!(Neighborhood::VonNeumann(3)
+            .offsets()
+            .all(|p| p.manhattan(Pos::ORIGIN) <= 3));
+
+        let knight = [Pos::new(1, 2), Pos::new(-2, 1)];
+        let custom: Vec<_> = Neighborhood::Custom(&knight).offsets().collect();
+        assert_eq!(custom, knight);
+    }
+}
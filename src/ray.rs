@@ -0,0 +1,123 @@
+use crate::geometry::gcd;
+use crate::matrix::Matrix;
+use crate::pos::Pos;
+use std::iter;
+
+impl<T> Matrix<T> {
+    /// The cells met walking from `from` by `step` (a [`Direction`] or any
+    /// non-zero vector), `from` itself excluded, until leaving the grid.
+    /// `from` may lie just off the edge to cast a ray into the grid.
+    ///
+    /// [`Direction`]: crate::pos::Direction
+    pub fn ray(&self, from: Pos, step: impl Into<Pos>) -> impl Iterator<Item = (Pos, &T)> + '_ {
+        let step = step.into();
+        assert_ne!(step, Pos::ORIGIN, "Cannot cast a ray with a zero step");
+        iter::successors(Some(from + step), move |&pos| Some(pos + step))
+            .map_while(move |pos| self.get_pos(pos).map(|t| (pos, t)))
+    }
+
+    /// The first cell along [`Matrix::ray`] matching `predicate`.
+    pub fn first_hit<F>(&self, from: Pos, step: impl Into<Pos>, predicate: F) -> Option<(Pos, &T)>
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.ray(from, step).find(|(_, t)| predicate(t))
+    }
+
+    /// Every cell that can be seen from `from`, row by row. A cell is hidden
+    /// when the straight line to it goes through the centre of a cell that
+    /// `blocks`, blocking cells themselves being visible.
+    pub fn visible_from<F>(&self, from: Pos, blocks: F) -> Vec<Pos>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let mut visible = Vec::new();
+        if !self.contains(from) {
+            return visible;
+        }
+        let (width, height) = (self.width() as i64, self.height() as i64);
+        // Every other cell lies on exactly one ray with a step in lowest terms
+        for dy in -from.y..height - from.y {
+            for dx in -from.x..width - from.x {
+                if gcd(dx.unsigned_abs().into(), dy.unsigned_abs().into()) != 1 {
+                    continue;
+                }
+                for (pos, cell) in self.ray(from, Pos::new(dx, dy)) {
+                    visible.push(pos);
+                    if blocks(cell) {
+                        break;
+                    }
+                }
+            }
+        }
+        visible.sort_by_key(|p| (p.y, p.x));
+        visible
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pos::Direction;
+
+    const GRID: &str = "\
+.#..#
+.....
+#####
+....#
+...##
+";
+
+    #[test]
+    fn test_ray_first_hit() {
+        let matrix = Matrix::parse_grid(GRID, Ok::<_, ()>).unwrap();
+        let ray: Vec<_> = matrix.ray(Pos::new(1, 1), Direction::East).collect();
+        assert_eq!(
+            ray,
+            vec![
+                (Pos::new(2, 1), &'.'),
+                (Pos::new(3, 1), &'.'),
+                (Pos::new(4, 1), &'.')
+            ]
+        );
+
+        // Entering from off the left edge
+        let hit = matrix.first_hit(Pos::new(-1, 3), Direction::East, |&c| c == '#');
+        assert_eq!(hit, Some((Pos::new(4, 3), &'#')));
+        let hit = matrix.first_hit(Pos::new(0, 0), Direction::SouthEast, |&c| c == '#');
+        assert_eq!(hit, Some((Pos::new(2, 2), &'#')));
+        // A knight's move skips over the wall
+        let jumps: Vec<_> = matrix
+            .ray(Pos::new(0, 0), Pos::new(1, 2))
+            .map(|(p, _)| p)
+            .collect();
+        assert_eq!(jumps, vec![Pos::new(1, 2), Pos::new(2, 4)]);
+        assert_eq!(
+            matrix.first_hit(Pos::new(0, 1), Direction::North, |&c| c == '#'),
+            None
+        );
+    }
+
+    #[test]
+    fn test_visible_from() {
+        let matrix = Matrix::parse_grid(".....\n.#...\n.....\n", Ok::<_, ()>).unwrap();
+        let blocks = |&c: &char| c == '#';
+
+        // Only the cell right behind the `#` is hidden, lines between cell
+        // centres passing beside it
+        let visible = matrix.visible_from(Pos::new(0, 0), blocks);
+        assert_eq!(visible.len(), 13);
+        assert_eq!(
+            visible[..3],
+            [Pos::new(1, 0), Pos::new(2, 0), Pos::new(3, 0)]
+        );
+        assert!(visible.contains(&Pos::new(1, 1)));
+        assert!(visible.contains(&Pos::new(4, 2)));
+        assert!(!visible.contains(&Pos::new(2, 2)));
+
+        let visible = matrix.visible_from(Pos::new(2, 2), blocks);
+        assert!(!visible.contains(&Pos::new(0, 0)));
+        assert_eq!(visible.len(), 13);
+        assert!(matrix.visible_from(Pos::new(5, 0), blocks).is_empty());
+    }
+}
@@ -0,0 +1,110 @@
+use crate::matrix::{Axis, Matrix};
+
+/// A mirror line found by [`Matrix::reflection_axes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reflection {
+    /// `Row` for a horizontal line mirroring rows onto each other, `Col` for
+    /// a vertical one mirroring columns.
+    pub axis: Axis,
+    /// Number of rows above, or columns left of, the line.
+    pub at: usize,
+    /// Pairs of mirrored cells that differ, the one before the line first.
+    pub mismatches: Vec<((usize, usize), (usize, usize))>,
+}
+
+impl<T> Matrix<T>
+where
+    T: Eq,
+{
+    /// Every line between two rows or columns across which the grid mirrors
+    /// itself, up to `tolerance` pairs of differing cells. Rows or columns
+    /// mirrored past the edge are ignored. Horizontal lines come first, each
+    /// kind from top left to bottom right.
+    pub fn reflection_axes(&self, tolerance: usize) -> Vec<Reflection> {
+        let mut found = Vec::new();
+        for axis in [Axis::Row, Axis::Col] {
+            let (len, across) = match axis {
+                Axis::Row => (self.height(), self.width()),
+                Axis::Col => (self.width(), self.height()),
+            };
+            let cell = |along: usize, i: usize| match axis {
+                Axis::Row => (i, along),
+                Axis::Col => (along, i),
+            };
+            'lines: for at in 1..len {
+                let mut mismatches = Vec::new();
+                for d in 0..at.min(len - at) {
+                    for i in 0..across {
+                        let (a, b) = (cell(at - 1 - d, i), cell(at + d, i));
+                        if self[a] != self[b] {
+                            if mismatches.len() == tolerance {
+                                continue 'lines;
+                            }
+                            mismatches.push((a, b));
+                        }
+                    }
+                }
+                found.push(Reflection {
+                    axis,
+                    at,
+                    mismatches,
+                });
+            }
+        }
+        found
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_reflection_axes() {
+        let vertical = "\
+#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.
+";
+        let horizontal = "\
+#...##..#
+#....#..#
+..##..###
+#####.##.
+#####.##.
+..##..###
+#....#..#
+";
+        let matrix = Matrix::parse_grid(vertical, Ok::<_, ()>).unwrap();
+        let exact = matrix.reflection_axes(0);
+        assert_eq!(
+            exact,
+            vec![Reflection {
+                axis: Axis::Col,
+                at: 5,
+                mismatches: vec![]
+            }]
+        );
+        let smudged: Vec<_> = matrix
+            .reflection_axes(1)
+            .into_iter()
+            .filter(|r| r.mismatches.len() == 1)
+            .collect();
+        assert_eq!(smudged.len(), 1);
+        assert_eq!((smudged[0].axis, smudged[0].at), (Axis::Row, 3));
+        assert_eq!(smudged[0].mismatches, vec![((0, 0), (0, 5))]);
+
+        let matrix = Matrix::parse_grid(horizontal, Ok::<_, ()>).unwrap();
+        let exact = matrix.reflection_axes(0);
+        assert_eq!(exact.len(), 1);
+        assert_eq!((exact[0].axis, exact[0].at), (Axis::Row, 4));
+        let smudged = matrix.reflection_axes(1);
+        assert!(smudged
+            .iter()
+            .any(|r| (r.axis, r.at, r.mismatches.len()) == (Axis::Row, 1, 1)));
+    }
+}
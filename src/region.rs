@@ -0,0 +1,162 @@
+use crate::matrix::Matrix;
+use crate::pos::{Neighborhood, Pos};
+use std::collections::VecDeque;
+
+/// A connected region found by [`Matrix::label_components`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    /// Top left corner of the bounding box.
+    pub min: Pos,
+    /// Bottom right corner of the bounding box, inclusive.
+    pub max: Pos,
+    pub members: Vec<Pos>,
+}
+
+impl Region {
+    pub fn size(&self) -> usize {
+        self.members.len()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Components {
+    /// Index into `regions` of the region each cell belongs to.
+    pub labels: Matrix<usize>,
+    pub regions: Vec<Region>,
+}
+
+impl<T> Matrix<T> {
+    /// Every cell reachable from `start` through rook moves, without leaving
+    /// the cells matching `predicate`. Empty if `start` itself does not match.
+    pub fn flood_fill<F>(&self, start: Pos, predicate: F) -> Vec<Pos>
+    where
+        F: Fn(&T) -> bool,
+    {
+        if !self.get_pos(start).is_some_and(&predicate) {
+            return Vec::new();
+        }
+        let mut seen = Matrix::new_default(self.width(), self.height());
+        self.fill_from(start, Neighborhood::Rook, &mut seen, |_, cell| {
+            predicate(cell)
+        })
+    }
+
+    /// Splits the matrix into regions of adjacent cells, where two neighbours
+    /// are in the same region when `same_region` holds for them. Every cell
+    /// is in some region, a cell joining no neighbour being one on its own.
+    pub fn label_components<F>(&self, neighborhood: Neighborhood<'_>, same_region: F) -> Components
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        let mut seen = Matrix::new_default(self.width(), self.height());
+        let mut labels = Matrix::new_default(self.width(), self.height());
+        let mut regions = Vec::new();
+        for (pos, _) in self.iter_pos() {
+            if seen[pos] {
+                continue;
+            }
+            let members = self.fill_from(Pos::from(pos), neighborhood, &mut seen, |from, to| {
+                same_region(&self[from], to)
+            });
+            let mut min = members[0];
+            let mut max = members[0];
+            for &p in &members {
+                labels[p] = regions.len();
+                min = Pos::new(min.x.min(p.x), min.y.min(p.y));
+                max = Pos::new(max.x.max(p.x), max.y.max(p.y));
+            }
+            regions.push(Region { min, max, members });
+        }
+        Components { labels, regions }
+    }
+
+    /// Breadth first fill from `start`, which must be in the matrix, over
+    /// cells not yet `seen`, stepping from `a` to `b` only when
+    /// `can_step(a, &self[b])` holds. `start` itself is always filled.
+    fn fill_from<F>(
+        &self,
+        start: Pos,
+        neighborhood: Neighborhood<'_>,
+        seen: &mut Matrix<bool>,
+        can_step: F,
+    ) -> Vec<Pos>
+    where
+        F: Fn(Pos, &T) -> bool,
+    {
+        let mut members = Vec::new();
+        let mut queue = VecDeque::from([start]);
+        seen[start] = true;
+        while let Some(pos) = queue.pop_front() {
+            members.push(pos);
+            for offset in neighborhood.offsets() {
+                let next = pos + offset;
+                if let Some(cell) = self.get_pos(next) {
+                    if !seen[next] && can_step(pos, cell) {
+                        seen[next] = true;
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+        members
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const GRID: &str = "\
+aab.
+a.bb
+.a..
+b.aa
+";
+
+    #[test]
+    fn test_flood_fill() {
+        let matrix = Matrix::parse_grid(GRID, Ok::<_, ()>).unwrap();
+        let mut filled = matrix.flood_fill(Pos::new(2, 2), |&c| c == '.');
+        filled.sort();
+        assert_eq!(filled, vec![Pos::new(2, 2), Pos::new(3, 2)]);
+        assert!(matrix.flood_fill(Pos::new(0, 0), |&c| c == '.').is_empty());
+        assert!(matrix.flood_fill(Pos::new(-1, 0), |_| true).is_empty());
+    }
+
+    #[test]
+    fn test_label_components() {
+        let matrix = Matrix::parse_grid(GRID, Ok::<_, ()>).unwrap();
+
+        let rook = matrix.label_components(Neighborhood::Rook, |a, b| a == b);
+        assert_eq!(rook.regions.len(), 10);
+        let b = &rook.regions[rook.labels[(2, 0)]];
+        assert_eq!(b.size(), 3);
+        assert_eq!((b.min, b.max), (Pos::new(2, 0), Pos::new(3, 1)));
+        assert_ne!(rook.labels[(1, 2)], rook.labels[(0, 0)]);
+
+        // Diagonals join all the `a`s, and the `.`s but the top right one
+        let king = matrix.label_components(Neighborhood::King, |a, b| a == b);
+        assert_eq!(king.labels[(1, 2)], king.labels[(0, 0)]);
+        assert_eq!(king.labels[(3, 3)], king.labels[(0, 0)]);
+        assert_eq!(king.labels[(1, 1)], king.labels[(3, 2)]);
+        assert_ne!(king.labels[(3, 0)], king.labels[(3, 2)]);
+        assert_eq!(king.regions.len(), 5);
+        let total: usize = king.regions.iter().map(Region::size).sum();
+        assert_eq!(total, matrix.len());
+    }
+
+    #[test]
+    fn test_label_components_irreflexive() {
+        // `#` is not even in the same region as itself
+        let matrix = Matrix::parse_grid("..#\n.##\n", Ok::<_, ()>).unwrap();
+        let open = matrix.label_components(Neighborhood::Rook, |a, b| *a != '#' && *b != '#');
+        assert_eq!(open.regions.len(), 4);
+        assert_eq!(open.regions[open.labels[(0, 0)]].size(), 3);
+        for wall in [(2, 0), (1, 1), (2, 1)] {
+            assert_eq!(
+                open.regions[open.labels[wall]].members,
+                vec![Pos::from(wall)]
+            );
+        }
+    }
+}
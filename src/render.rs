@@ -0,0 +1,218 @@
+use crate::matrix::Matrix;
+use crate::pos::Pos;
+use std::collections::HashSet;
+use std::fmt;
+use std::io::{self, IsTerminal};
+
+/// How an [`Overlay`] stands out when colours are on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Highlight {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    Inverse,
+}
+
+impl Highlight {
+    fn ansi(self) -> &'static str {
+        match self {
+            Highlight::Red => "\x1b[1;31m",
+            Highlight::Green => "\x1b[1;32m",
+            Highlight::Yellow => "\x1b[1;33m",
+            Highlight::Blue => "\x1b[1;34m",
+            Highlight::Magenta => "\x1b[1;35m",
+            Highlight::Cyan => "\x1b[1;36m",
+            Highlight::Inverse => "\x1b[7m",
+        }
+    }
+}
+
+const RESET: &str = "\x1b[0m";
+
+/// A set of cells drawn on top of the matrix. With colours on the cells keep
+/// their glyph and get highlighted, otherwise the glyph is replaced by `mark`.
+#[derive(Debug, Clone)]
+pub struct Overlay {
+    cells: HashSet<Pos>,
+    highlight: Highlight,
+    mark: char,
+}
+
+impl Overlay {
+    pub fn new(cells: impl IntoIterator<Item = Pos>, highlight: Highlight, mark: char) -> Self {
+        Overlay {
+            cells: cells.into_iter().collect(),
+            highlight,
+            mark,
+        }
+    }
+
+    pub fn path(path: impl IntoIterator<Item = Pos>) -> Self {
+        Overlay::new(path, Highlight::Yellow, '*')
+    }
+
+    pub fn marked(cells: impl IntoIterator<Item = Pos>) -> Self {
+        Overlay::new(cells, Highlight::Red, '#')
+    }
+
+    pub fn cursor(pos: Pos) -> Self {
+        Overlay::new([pos], Highlight::Inverse, '@')
+    }
+
+    pub fn highlight(mut self, highlight: Highlight) -> Self {
+        self.highlight = highlight;
+        self
+    }
+
+    pub fn mark(mut self, mark: char) -> Self {
+        self.mark = mark;
+        self
+    }
+}
+
+/// Text rendering of a [`Matrix`], see [`Matrix::render`].
+pub struct Render<'a, T, F> {
+    matrix: &'a Matrix<T>,
+    glyph: F,
+    rulers: bool,
+    overlays: Vec<Overlay>,
+    color: Option<bool>,
+}
+
+impl<T> Matrix<T> {
+    /// Renders each cell with `glyph`, padding all columns to the same width.
+    pub fn render<F, D>(&self, glyph: F) -> Render<'_, T, F>
+    where
+        F: Fn(&T) -> D,
+        D: fmt::Display,
+    {
+        Render {
+            matrix: self,
+            glyph,
+            rulers: false,
+            overlays: Vec::new(),
+            color: None,
+        }
+    }
+}
+
+impl<T, F, D> Render<'_, T, F>
+where
+    F: Fn(&T) -> D,
+    D: fmt::Display,
+{
+    /// Adds the x coordinates above the grid and the y coordinates on its
+    /// left. Coordinates wider than a column only show their last digits.
+    pub fn rulers(mut self, rulers: bool) -> Self {
+        self.rulers = rulers;
+        self
+    }
+
+    /// Draws `overlay` on top of the ones added before it.
+    pub fn overlay(mut self, overlay: Overlay) -> Self {
+        self.overlays.push(overlay);
+        self
+    }
+
+    /// Forces colours on or off. By default [`Render::print`] only uses them
+    /// when stdout is a terminal, and `Display` never does.
+    pub fn color(mut self, color: bool) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn print(&self) {
+        let color = self.color.unwrap_or_else(|| io::stdout().is_terminal());
+        let mut out = String::new();
+        // Writing to a String cannot fail
+        let _ = self.write(&mut out, color);
+        print!("{out}");
+    }
+
+    fn write<W: fmt::Write>(&self, f: &mut W, color: bool) -> fmt::Result {
+        let width = self.matrix.width();
+        let glyphs: Vec<String> = self
+            .matrix
+            .vec
+            .iter()
+            .map(|t| (self.glyph)(t).to_string())
+            .collect();
+        let cell_width = glyphs.iter().map(|g| g.chars().count()).max().unwrap_or(1);
+        let sep = if cell_width > 1 { " " } else { "" };
+        let label_width = self.matrix.height().saturating_sub(1).to_string().len();
+
+        if self.rulers {
+            write!(f, "{:label_width$} ", "")?;
+            let modulo = 10usize.saturating_pow(cell_width as u32);
+            for x in 0..width {
+                if x > 0 {
+                    write!(f, "{sep}")?;
+                }
+                write!(f, "{:>cell_width$}", x % modulo)?;
+            }
+            writeln!(f)?;
+        }
+        for (y, row) in glyphs.chunks(width.max(1)).enumerate() {
+            if self.rulers {
+                write!(f, "{y:>label_width$} ")?;
+            }
+            for (x, glyph) in row.iter().enumerate() {
+                if x > 0 {
+                    write!(f, "{sep}")?;
+                }
+                let pos = Pos::from((x, y));
+                match self.overlays.iter().rev().find(|o| o.cells.contains(&pos)) {
+                    Some(o) if color => {
+                        write!(f, "{}{glyph:>cell_width$}{RESET}", o.highlight.ansi())?
+                    }
+                    Some(o) => write!(f, "{:>cell_width$}", o.mark)?,
+                    None => write!(f, "{glyph:>cell_width$}")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T, F, D> fmt::Display for Render<'_, T, F>
+where
+    F: Fn(&T) -> D,
+    D: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, self.color.unwrap_or(false))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render_padding_rulers() {
+        let matrix = Matrix::new([1, 20, 3, 400, 5, 6], 3, 2);
+        let out = matrix.render(|n| *n).rulers(true).to_string();
+        assert_eq!(out, "    0   1   2\n0   1  20   3\n1 400   5   6\n");
+    }
+
+    #[test]
+    fn test_render_overlays() {
+        let matrix = Matrix::parse_grid("...\n...\n", Ok::<_, ()>).unwrap();
+        let render = matrix
+            .render(|c| *c)
+            .overlay(Overlay::path([
+                Pos::new(0, 0),
+                Pos::new(1, 0),
+                Pos::new(1, 1),
+            ]))
+            .overlay(Overlay::cursor(Pos::new(1, 1)));
+        assert_eq!(render.to_string(), "**.\n.@.\n");
+
+        let colored = render.color(true).to_string();
+        assert_eq!(colored.lines().nth(1), Some(".\x1b[7m.\x1b[0m."));
+    }
+}
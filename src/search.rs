@@ -0,0 +1,378 @@
+use crate::matrix::Matrix;
+use crate::pos::{Direction, Pos};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+/// A search state that sits on a grid cell. It can carry more than the
+/// position, like a heading or how many steps were taken in a straight line.
+pub trait GridState: Clone + Eq + Hash {
+    fn pos(&self) -> Pos;
+}
+
+impl GridState for Pos {
+    fn pos(&self) -> Pos {
+        *self
+    }
+}
+
+/// Gives the states reachable in one move from `state`, with the cost of
+/// each move. Implemented for any `FnMut(&S) -> impl IntoIterator<Item = (S, u64)>`.
+pub trait Successors<S> {
+    fn successors(&mut self, state: &S) -> impl IntoIterator<Item = (S, u64)>;
+}
+
+impl<S, F, I> Successors<S> for F
+where
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, u64)>,
+{
+    fn successors(&mut self, state: &S) -> impl IntoIterator<Item = (S, u64)> {
+        self(state)
+    }
+}
+
+/// Outcome of a search: the final distance of every settled state, and the
+/// way back to a start from each of them.
+#[derive(Debug, Clone)]
+pub struct Search<S> {
+    dist: HashMap<S, u64>,
+    parent: HashMap<S, S>,
+    goal: Option<S>,
+}
+
+impl<S> Search<S>
+where
+    S: Clone + Eq + Hash,
+{
+    fn new() -> Self {
+        Search {
+            dist: HashMap::new(),
+            parent: HashMap::new(),
+            goal: None,
+        }
+    }
+
+    /// The first goal state reached, if the search had one.
+    pub fn goal(&self) -> Option<&S> {
+        self.goal.as_ref()
+    }
+
+    /// Cost of the cheapest path to the goal.
+    pub fn cost(&self) -> Option<u64> {
+        self.goal.as_ref().and_then(|g| self.distance(g))
+    }
+
+    pub fn distance(&self, state: &S) -> Option<u64> {
+        self.dist.get(state).copied()
+    }
+
+    pub fn distances(&self) -> &HashMap<S, u64> {
+        &self.dist
+    }
+
+    /// Path to the goal, both ends included.
+    pub fn path(&self) -> Option<Vec<S>> {
+        self.goal.as_ref().and_then(|g| self.path_to(g))
+    }
+
+    /// Path from the start to `state`, both ends included.
+    pub fn path_to(&self, state: &S) -> Option<Vec<S>> {
+        if !self.dist.contains_key(state) {
+            return None;
+        }
+        let mut path = vec![state.clone()];
+        let mut current = state;
+        while let Some(prev) = self.parent.get(current) {
+            path.push(prev.clone());
+            current = prev;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Shortest distance to each cell of a `width` by `height` grid, over all
+    /// the states standing on it.
+    pub fn distance_map(&self, width: usize, height: usize) -> Matrix<Option<u64>>
+    where
+        S: GridState,
+    {
+        let mut map: Matrix<Option<u64>> = Matrix::new_default(width, height);
+        for (state, &d) in &self.dist {
+            if let Some(cell) = map.get_pos_mut(state.pos()) {
+                *cell = Some(cell.map_or(d, |c| c.min(d)));
+            }
+        }
+        map
+    }
+}
+
+/// Breadth first search where every move costs 1. Stops at the first state
+/// for which `is_goal` holds, pass `|_| false` to explore everything.
+pub fn bfs<S, F, I, G>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: F,
+    mut is_goal: G,
+) -> Search<S>
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+    G: FnMut(&S) -> bool,
+{
+    let mut search = Search::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if !search.dist.contains_key(&start) {
+            search.dist.insert(start.clone(), 0);
+            queue.push_back(start);
+        }
+    }
+    while let Some(state) = queue.pop_front() {
+        if is_goal(&state) {
+            search.goal = Some(state);
+            break;
+        }
+        let d = search.dist[&state];
+        for next in successors(&state) {
+            if !search.dist.contains_key(&next) {
+                search.dist.insert(next.clone(), d + 1);
+                search.parent.insert(next.clone(), state.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+    search
+}
+
+/// Dijkstra's algorithm. Stops at the first state for which `is_goal` holds,
+/// pass `|_| false` to explore everything.
+pub fn dijkstra<S, N, G>(
+    starts: impl IntoIterator<Item = S>,
+    successors: N,
+    is_goal: G,
+) -> Search<S>
+where
+    S: Clone + Eq + Hash,
+    N: Successors<S>,
+    G: FnMut(&S) -> bool,
+{
+    astar(starts, successors, |_| 0, is_goal)
+}
+
+/// A* search. `heuristic` must never overestimate the remaining cost, nor drop
+/// by more than the cost of a move, or the path found might not be the cheapest.
+pub fn astar<S, N, H, G>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: N,
+    mut heuristic: H,
+    mut is_goal: G,
+) -> Search<S>
+where
+    S: Clone + Eq + Hash,
+    N: Successors<S>,
+    H: FnMut(&S) -> u64,
+    G: FnMut(&S) -> bool,
+{
+    let mut search = Search::new();
+    let mut best: HashMap<S, u64> = HashMap::new();
+    let mut parent: HashMap<S, S> = HashMap::new();
+    // The heap only holds indices into `states`, so states need not be `Ord`
+    let mut states = Vec::new();
+    let mut heap = BinaryHeap::new();
+    for start in starts {
+        if !best.contains_key(&start) {
+            best.insert(start.clone(), 0);
+            heap.push(Reverse((heuristic(&start), 0, states.len())));
+            states.push(start);
+        }
+    }
+    while let Some(Reverse((_, d, idx))) = heap.pop() {
+        let state = states[idx].clone();
+        if search.dist.contains_key(&state) || best[&state] < d {
+            continue;
+        }
+        search.dist.insert(state.clone(), d);
+        if let Some(p) = parent.get(&state) {
+            search.parent.insert(state.clone(), p.clone());
+        }
+        if is_goal(&state) {
+            search.goal = Some(state);
+            break;
+        }
+        for (next, cost) in successors.successors(&state) {
+            let nd = d + cost;
+            if search.dist.contains_key(&next) || best.get(&next).is_some_and(|&b| b <= nd) {
+                continue;
+            }
+            best.insert(next.clone(), nd);
+            parent.insert(next.clone(), state.clone());
+            heap.push(Reverse((nd + heuristic(&next), nd, states.len())));
+            states.push(next);
+        }
+    }
+    search
+}
+
+impl<T> Matrix<T> {
+    /// Distances from `start` moving like a rook through cells that are
+    /// `passable`.
+    pub fn bfs<F>(&self, start: Pos, passable: F) -> Search<Pos>
+    where
+        F: Fn(&T) -> bool,
+    {
+        bfs(
+            [start],
+            |&pos| {
+                self.rook_neighbor_pos(pos)
+                    .filter(|&p| passable(&self[p]))
+                    .collect::<Vec<_>>()
+            },
+            |_| false,
+        )
+    }
+
+    /// Cheapest costs from `start` moving like a rook. `cost` is called with
+    /// the cell moved from, the cell moved to and the content of the latter,
+    /// and returns `None` when the move is not allowed.
+    pub fn dijkstra<F>(&self, start: Pos, cost: F) -> Search<Pos>
+    where
+        F: Fn(Pos, Pos, &T) -> Option<u64>,
+    {
+        dijkstra([start], self.rook_moves(cost), |_| false)
+    }
+
+    /// Cheapest path from `start` to `goal` moving like a rook, see
+    /// [`Matrix::dijkstra`] for `cost`.
+    pub fn astar<F, H>(&self, start: Pos, goal: Pos, cost: F, heuristic: H) -> Search<Pos>
+    where
+        F: Fn(Pos, Pos, &T) -> Option<u64>,
+        H: Fn(Pos) -> u64,
+    {
+        astar(
+            [start],
+            self.rook_moves(cost),
+            |&pos| heuristic(pos),
+            |&pos| pos == goal,
+        )
+    }
+
+    fn rook_moves<'a, F>(&'a self, cost: F) -> impl FnMut(&Pos) -> Vec<(Pos, u64)> + 'a
+    where
+        F: Fn(Pos, Pos, &T) -> Option<u64> + 'a,
+    {
+        move |&pos| {
+            Direction::CARDINAL
+                .into_iter()
+                .map(|d| pos.step(d))
+                .filter_map(|p| {
+                    let to = self.get_pos(p)?;
+                    cost(pos, p, to).map(|c| (p, c))
+                })
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const MAZE: &str = "\
+S.#.....
+.##.###.
+....#...
+.##...#E
+";
+
+    fn maze() -> (Matrix<char>, Pos, Pos) {
+        let matrix = Matrix::parse_grid(MAZE, Ok::<_, ()>).unwrap();
+        let start = Pos::from(matrix.index_of(|&c| c == 'S').unwrap());
+        let end = Pos::from(matrix.index_of(|&c| c == 'E').unwrap());
+        (matrix, start, end)
+    }
+
+    #[test]
+    fn test_bfs() {
+        let (matrix, start, end) = maze();
+        let search = matrix.bfs(start, |&c| c != '#');
+        assert_eq!(search.distance(&end), Some(12));
+        let path = search.path_to(&end).unwrap();
+        assert_eq!(path.len(), 13);
+        assert_eq!((path[0], path[12]), (start, end));
+        assert!(path.windows(2).all(|w| w[0].manhattan(w[1]) == 1));
+
+        let map = search.distance_map(matrix.width(), matrix.height());
+        assert_eq!(map[(2, 0)], None);
+        assert_eq!(map[(0, 2)], Some(2));
+    }
+
+    #[test]
+    fn test_dijkstra_astar() {
+        let (matrix, start, end) = maze();
+        // Walking on '.' costs 1, going through a wall costs 5
+        let cost = |_, _, c: &char| Some(if *c == '#' { 5 } else { 1 });
+        let search = matrix.dijkstra(start, cost);
+        assert_eq!(search.distance(&end), Some(12));
+        assert_eq!(search.distance(&Pos::new(2, 0)), Some(6));
+
+        let search = matrix.astar(start, end, cost, |p| p.manhattan(end));
+        assert_eq!(search.cost(), Some(12));
+        assert_eq!(search.path().unwrap().last(), Some(&end));
+    }
+
+    #[test]
+    fn test_state_beyond_position() {
+        // Never go more than 2 steps in a row in the same direction
+        #[derive(Clone, PartialEq, Eq, Hash)]
+        struct Walker {
+            pos: Pos,
+            heading: Direction,
+            straight: u8,
+        }
+        impl GridState for Walker {
+            fn pos(&self) -> Pos {
+                self.pos
+            }
+        }
+
+        let matrix = Matrix::new_with(6, 1, || 1u64);
+        let start = Walker {
+            pos: Pos::new(0, 0),
+            heading: Direction::East,
+            straight: 0,
+        };
+        let search = dijkstra(
+            [start],
+            |w: &Walker| {
+                let mut next = Vec::new();
+                for heading in [w.heading, w.heading.turn_left(), w.heading.turn_right()] {
+                    let straight = if heading == w.heading {
+                        w.straight + 1
+                    } else {
+                        1
+                    };
+                    let pos = w.pos.step(heading);
+                    if straight <= 2 {
+                        if let Some(&c) = matrix.get_pos(pos) {
+                            next.push((
+                                Walker {
+                                    pos,
+                                    heading,
+                                    straight,
+                                },
+                                c,
+                            ));
+                        }
+                    }
+                }
+                next
+            },
+            |w| w.pos == Pos::new(5, 0),
+        );
+        // A single row leaves no room to break the straight line
+        assert_eq!(search.cost(), None);
+        let map = search.distance_map(6, 1);
+        assert_eq!(map.vec, vec![Some(0), Some(1), Some(2), None, None, None]);
+    }
+}
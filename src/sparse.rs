@@ -0,0 +1,194 @@
+use crate::matrix::Matrix;
+use crate::pos::{Direction, Pos};
+use std::collections::HashMap;
+use std::ops;
+
+/// An unbounded grid that only stores the cells that were written to, every
+/// other cell holds `default`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Pos, T>,
+    default: T,
+    bounds: Option<(Pos, Pos)>,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new(default: T) -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+            default,
+            bounds: None,
+        }
+    }
+
+    /// Builds a grid from the cells of `matrix` that are not `default`,
+    /// with the top left of the matrix at `(0, 0)`.
+    pub fn from_matrix(matrix: Matrix<T>, default: T) -> Self
+    where
+        T: PartialEq,
+    {
+        let width = matrix.width();
+        let mut grid = SparseGrid::new(default);
+        for (i, item) in matrix.vec.into_iter().enumerate() {
+            if item != grid.default {
+                grid.insert(Pos::from((i % width, i / width)), item);
+            }
+        }
+        grid
+    }
+
+    /// Crops the cells written to into a dense matrix, whose `(0, 0)` is
+    /// the top left corner of [`SparseGrid::bounds`]. Panics if the number
+    /// of cells in the bounds does not fit in a `usize`; a count that does
+    /// but is still too large to allocate aborts like any other allocation.
+    pub fn to_matrix(&self) -> Matrix<T>
+    where
+        T: Clone,
+    {
+        let Some((min, max)) = self.bounds else {
+            return Matrix::new([], 0, 0);
+        };
+        let span = |lo: i64, hi: i64| {
+            hi.abs_diff(lo)
+                .checked_add(1)
+                .and_then(|n| usize::try_from(n).ok())
+        };
+        let (width, height) = match (span(min.x, max.x), span(min.y, max.y)) {
+            (Some(w), Some(h)) if w.checked_mul(h).is_some() => (w, h),
+            _ => panic!(
+                "SparseGrid with bounds {min:?} to {max:?} is too large to turn into a Matrix"
+            ),
+        };
+        let items = (0..width * height).map(|i| {
+            let pos = min + Pos::from((i % width, i / width));
+            self.get(pos).clone()
+        });
+        Matrix::new(items, width, height)
+    }
+
+    pub fn default_value(&self) -> &T {
+        &self.default
+    }
+
+    /// Top left and bottom right corners, inclusive, of every cell ever
+    /// written to, even ones set back to the default value: the bounds never
+    /// shrink. `None` while the grid is empty.
+    pub fn bounds(&self) -> Option<(Pos, Pos)> {
+        self.bounds
+    }
+
+    /// Number of cells written to.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, pos: Pos) -> &T {
+        self.cells.get(&pos).unwrap_or(&self.default)
+    }
+
+    /// Mutable access to the cell at `pos`, which counts as written to.
+    pub fn get_mut(&mut self, pos: Pos) -> &mut T
+    where
+        T: Clone,
+    {
+        self.grow(pos);
+        self.cells
+            .entry(pos)
+            .or_insert_with(|| self.default.clone())
+    }
+
+    pub fn insert(&mut self, pos: Pos, value: T) -> Option<T> {
+        self.grow(pos);
+        self.cells.insert(pos, value)
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        self.cells.contains_key(&pos)
+    }
+
+    pub fn rook_neighbor_pos(&self, pos: Pos) -> impl Iterator<Item = Pos> {
+        Direction::CARDINAL.into_iter().map(move |d| pos.step(d))
+    }
+
+    pub fn neighbor_pos(&self, pos: Pos) -> impl Iterator<Item = Pos> {
+        Direction::ALL.into_iter().map(move |d| pos.step(d))
+    }
+
+    /// The cells written to, in no particular order.
+    pub fn iter_pos(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.cells.iter().map(|(pos, t)| (*pos, t))
+    }
+
+    fn grow(&mut self, pos: Pos) {
+        self.bounds = Some(match self.bounds {
+            None => (pos, pos),
+            Some((min, max)) => (
+                Pos::new(min.x.min(pos.x), min.y.min(pos.y)),
+                Pos::new(max.x.max(pos.x), max.y.max(pos.y)),
+            ),
+        });
+    }
+}
+
+impl<T> ops::Index<Pos> for SparseGrid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &Self::Output {
+        self.get(pos)
+    }
+}
+
+impl<T: Clone> ops::IndexMut<Pos> for SparseGrid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut Self::Output {
+        self.get_mut(pos)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sparse_grid() {
+        let mut grid = SparseGrid::new('.');
+        assert_eq!(grid.bounds(), None);
+        assert_eq!(grid[Pos::new(-100, 7)], '.');
+
+        grid[Pos::new(-2, 1)] = '#';
+        grid.insert(Pos::new(1, -1), '@');
+        assert_eq!(grid.bounds(), Some((Pos::new(-2, -1), Pos::new(1, 1))));
+        assert_eq!(grid.len(), 2);
+        assert_eq!(
+            grid.rook_neighbor_pos(Pos::new(-2, 0))
+                .filter(|p| grid[*p] == '#')
+                .count(),
+            1
+        );
+
+        let matrix = grid.to_matrix();
+        assert_eq!((matrix.width(), matrix.height()), (4, 3));
+        assert_eq!(matrix.vec.iter().collect::<String>(), "...@....#...");
+
+        let back = SparseGrid::from_matrix(matrix, '.');
+        assert_eq!(back.len(), 2);
+        assert_eq!(back[Pos::new(3, 0)], '@');
+        assert_eq!(back.bounds(), Some((Pos::new(0, 0), Pos::new(3, 2))));
+
+        // Writing the default back does not shrink the bounds
+        grid.insert(Pos::new(1, -1), '.');
+        assert_eq!(grid.bounds(), Some((Pos::new(-2, -1), Pos::new(1, 1))));
+    }
+
+    #[test]
+    #[should_panic(expected = "too large to turn into a Matrix")]
+    fn test_to_matrix_extreme_bounds() {
+        let mut grid = SparseGrid::new(0u8);
+        grid.insert(Pos::new(i64::MIN, 0), 1);
+        grid.insert(Pos::new(i64::MAX, 0), 1);
+        grid.to_matrix();
+    }
+}
@@ -0,0 +1,230 @@
+use crate::matrix::{Matrix, MatrixError};
+use crate::pos::Pos;
+use std::ops;
+
+/// A borrowed rectangle of a [`Matrix`]. Coordinates are relative to its top
+/// left corner.
+#[derive(Debug, Clone, Copy)]
+pub struct MatrixView<'a, T> {
+    matrix: &'a Matrix<T>,
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Matrix<T> {
+    pub fn view(&self, x: usize, y: usize, width: usize, height: usize) -> MatrixView<'_, T> {
+        self.try_view(x, y, width, height)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_view(
+        &self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> Result<MatrixView<'_, T>, MatrixError> {
+        if x.checked_add(width)
+            .is_none_or(|right| right > self.width())
+            || y.checked_add(height)
+                .is_none_or(|bottom| bottom > self.height())
+        {
+            return Err(MatrixError::RectOutOfBounds {
+                x,
+                y,
+                w: width,
+                h: height,
+                width: self.width(),
+                height: self.height(),
+            });
+        }
+        Ok(MatrixView {
+            matrix: self,
+            x,
+            y,
+            width,
+            height,
+        })
+    }
+
+    /// Every `width` by `height` view, row by row, overlapping each other.
+    /// Panics if `width` or `height` is 0, like [`slice::windows`].
+    pub fn windows(&self, width: usize, height: usize) -> impl Iterator<Item = MatrixView<'_, T>> {
+        assert!(
+            width > 0 && height > 0,
+            "Window size ({width}, {height}) must be non-zero"
+        );
+        let xs = (self.width() + 1).saturating_sub(width);
+        let ys = (self.height() + 1).saturating_sub(height);
+        (0..ys).flat_map(move |y| (0..xs).map(move |x| self.view(x, y, width, height)))
+    }
+}
+
+impl<'a, T> MatrixView<'a, T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn len(&self) -> usize {
+        self.width * self.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Position of the top left corner in the underlying matrix.
+    pub fn origin(&self) -> (usize, usize) {
+        (self.x, self.y)
+    }
+
+    pub fn get(&self, x: isize, y: isize) -> Option<&'a T> {
+        let x = x.try_into().ok()?;
+        let y = y.try_into().ok()?;
+        self.try_get(x, y).ok()
+    }
+
+    pub fn get_pos(&self, pos: Pos) -> Option<&'a T> {
+        let (x, y) = pos.try_into().ok()?;
+        self.try_get(x, y).ok()
+    }
+
+    pub fn try_get(&self, x: usize, y: usize) -> Result<&'a T, MatrixError> {
+        if x >= self.width || y >= self.height {
+            return Err(MatrixError::OutOfBounds {
+                x,
+                y,
+                width: self.width,
+                height: self.height,
+            });
+        }
+        self.matrix.try_get(self.x + x, self.y + y)
+    }
+
+    pub fn row(&self, y: usize) -> &'a [T] {
+        if y >= self.height {
+            panic!(
+                "{}",
+                MatrixError::RowOutOfBounds {
+                    y,
+                    height: self.height
+                }
+            );
+        }
+        &self.matrix.row(self.y + y)[self.x..self.x + self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &'a [T]> + '_ {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    pub fn iter_pos(&self) -> impl Iterator<Item = ((usize, usize), &'a T)> + '_ {
+        self.rows()
+            .enumerate()
+            .flat_map(|(y, row)| row.iter().enumerate().map(move |(x, t)| ((x, y), t)))
+    }
+
+    /// A view of a rectangle inside this one.
+    pub fn view(&self, x: usize, y: usize, width: usize, height: usize) -> MatrixView<'a, T> {
+        if x.checked_add(width).is_none_or(|right| right > self.width)
+            || y.checked_add(height)
+                .is_none_or(|bottom| bottom > self.height)
+        {
+            panic!(
+                "{}",
+                MatrixError::RectOutOfBounds {
+                    x,
+                    y,
+                    w: width,
+                    h: height,
+                    width: self.width,
+                    height: self.height,
+                }
+            );
+        }
+        self.matrix.view(self.x + x, self.y + y, width, height)
+    }
+
+    /// Copies the rectangle into its own matrix.
+    pub fn crop(&self) -> Matrix<T>
+    where
+        T: Clone,
+    {
+        let items = self.rows().flat_map(|row| row.iter().cloned());
+        Matrix::new(items, self.width, self.height)
+    }
+}
+
+impl<T> ops::Index<(usize, usize)> for MatrixView<'_, T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
+        self.try_get(x, y).unwrap_or_else(|e| panic!("{e}"))
+    }
+}
+
+impl<T> ops::Index<Pos> for MatrixView<'_, T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &Self::Output {
+        self.get_pos(pos).unwrap_or_else(|| {
+            panic!(
+                "Index {:?} out of range for MatrixView with size ({}, {})",
+                pos, self.width, self.height
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_view() {
+        //  0  1  2  3
+        //  4  5  6  7
+        //  8  9 10 11
+        let matrix = Matrix::new(0..12, 4, 3);
+        let view = matrix.view(1, 1, 3, 2);
+        assert_eq!(view[(0, 0)], 5);
+        assert_eq!(view[Pos::new(2, 1)], 11);
+        assert_eq!(view.get(3, 0), None);
+        assert_eq!(view.row(1), &[9, 10, 11]);
+        let items: Vec<_> = view.iter_pos().map(|(pos, &t)| (pos, t)).collect();
+        assert_eq!(items[4], ((1, 1), 10));
+        assert_eq!(
+            view.view(1, 0, 2, 2).crop(),
+            Matrix::new([6, 7, 10, 11], 2, 2)
+        );
+        assert!(matrix.try_view(2, 0, 3, 1).is_err());
+        assert!(matrix.try_view(usize::MAX, 0, 2, 1).is_err());
+        assert!(matrix.try_view(0, 1, 1, usize::MAX).is_err());
+    }
+
+    #[test]
+    fn test_windows() {
+        let matrix = Matrix::new(0..12, 4, 3);
+        let windows: Vec<_> = matrix.windows(2, 2).collect();
+        assert_eq!(windows.len(), 6);
+        assert_eq!(windows[5].origin(), (2, 1));
+        let sums: Vec<i32> = windows
+            .iter()
+            .map(|w| w.iter_pos().map(|(_, t)| t).sum())
+            .collect();
+        assert_eq!(sums, vec![10, 14, 18, 26, 30, 34]);
+        assert_eq!(matrix.windows(5, 1).count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Window size (0, 2) must be non-zero")]
+    fn test_windows_empty() {
+        Matrix::new(0..6, 3, 2).windows(0, 2).count();
+    }
+}
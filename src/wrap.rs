@@ -0,0 +1,102 @@
+use crate::matrix::{Matrix, MatrixError};
+use crate::pos::{Direction, Pos};
+use std::ops;
+
+/// A [`Matrix`] repeated forever in every direction. Position `(0, 0)` is the
+/// top left cell of tile `(0, 0)`, the original grid. The matrix always has
+/// at least one cell.
+#[derive(Debug, Clone, Copy)]
+pub struct Wrapped<'a, T> {
+    matrix: &'a Matrix<T>,
+}
+
+impl<T> Matrix<T> {
+    /// Panics if the matrix has no cells, see [`Matrix::try_wrapped`].
+    pub fn wrapped(&self) -> Wrapped<'_, T> {
+        self.try_wrapped().unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_wrapped(&self) -> Result<Wrapped<'_, T>, MatrixError> {
+        if self.is_empty() {
+            return Err(MatrixError::Empty {
+                width: self.width(),
+                height: self.height(),
+            });
+        }
+        Ok(Wrapped { matrix: self })
+    }
+}
+
+impl<'a, T> Wrapped<'a, T> {
+    pub fn matrix(&self) -> &'a Matrix<T> {
+        self.matrix
+    }
+
+    /// Position in the original grid matching `pos`, and the copy of the
+    /// grid, as `(tile_x, tile_y)`, it lies in.
+    pub fn locate(&self, pos: Pos) -> (Pos, (i64, i64)) {
+        let width = self.matrix.width() as i64;
+        let height = self.matrix.height() as i64;
+        let base = Pos::new(pos.x.rem_euclid(width), pos.y.rem_euclid(height));
+        let tile = (pos.x.div_euclid(width), pos.y.div_euclid(height));
+        (base, tile)
+    }
+
+    pub fn get(&self, pos: Pos) -> &'a T {
+        let (base, _) = self.locate(pos);
+        &self.matrix[base]
+    }
+
+    pub fn rook_neighbor_pos(&self, pos: Pos) -> impl Iterator<Item = Pos> {
+        Direction::CARDINAL.into_iter().map(move |d| pos.step(d))
+    }
+
+    pub fn neighbor_pos(&self, pos: Pos) -> impl Iterator<Item = Pos> {
+        Direction::ALL.into_iter().map(move |d| pos.step(d))
+    }
+}
+
+impl<T> ops::Index<Pos> for Wrapped<'_, T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &Self::Output {
+        self.get(pos)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_wrapped() {
+        // 0 1 2
+        // 3 4 5
+        let matrix = Matrix::new(0..6, 3, 2);
+        let wrapped = matrix.wrapped();
+        assert_eq!(wrapped[Pos::new(1, 1)], 4);
+        assert_eq!(wrapped.locate(Pos::new(3, 0)), (Pos::new(0, 0), (1, 0)));
+        assert_eq!(wrapped.locate(Pos::new(-1, -1)), (Pos::new(2, 1), (-1, -1)));
+        assert_eq!(wrapped.locate(Pos::new(-4, 5)), (Pos::new(2, 1), (-2, 2)));
+        assert_eq!(wrapped[Pos::new(-4, 5)], 5);
+
+        let around: Vec<_> = wrapped
+            .rook_neighbor_pos(Pos::new(0, 0))
+            .map(|p| wrapped[p])
+            .collect();
+        assert_eq!(around, vec![3, 1, 3, 2]);
+        assert_eq!(wrapped.neighbor_pos(Pos::new(0, 0)).count(), 8);
+    }
+
+    #[test]
+    fn test_try_wrapped_empty() {
+        for (width, height) in [(0, 0), (0, 3), (3, 0)] {
+            let matrix: Matrix<u8> = Matrix::new([], width, height);
+            assert_eq!(
+                matrix.try_wrapped().err(),
+                Some(MatrixError::Empty { width, height })
+            );
+        }
+        assert!(Matrix::new([1], 1, 1).try_wrapped().is_ok());
+    }
+}